@@ -0,0 +1,141 @@
+//! Validation of textual IPFS content identifiers used as document hashes.
+
+use sp_std::vec::Vec;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Multihash code of sha2-256, the only hash function a CIDv0 can use.
+const SHA2_256: u64 = 0x12;
+
+/// Multicodec of dag-pb, the implicit content codec of a CIDv0.
+const DAG_PB: u64 = 0x70;
+
+/// Longest textual CID accepted, enough for a base32 CIDv1 with a 512-bit digest.
+pub const MAX_CID_LEN: usize = 128;
+
+/// Largest multihash digest accepted, in bytes.
+const MAX_DIGEST_LEN: u64 = 64;
+
+/// A CID reduced to what identifies the content, whatever its version and multibase.
+struct Cid<'a> {
+	codec: u64,
+	hash: u64,
+	digest: &'a [u8],
+}
+
+/// Returns true if `cid` is a base58btc CIDv0 (`Qm...`) or a base32 CIDv1 (`b...`) wrapping a
+/// well-formed multihash.
+pub fn is_valid_cid(cid: &[u8]) -> bool {
+	binary_cid(cid).is_some()
+}
+
+/// The binary CIDv1 form of `cid`, which is the same for the CIDv0 and CIDv1 spellings of the
+/// same content. None if `cid` is not valid.
+pub fn binary_cid(cid: &[u8]) -> Option<Vec<u8>> {
+	if cid.len() > MAX_CID_LEN {
+		return None
+	}
+	let bytes = match cid {
+		[b'Q', b'm', ..] if cid.len() == 46 => decode_base58(cid)?,
+		[b'b', rest @ ..] => decode_base32(rest)?,
+		_ => return None,
+	};
+	let cid = match cid[0] {
+		b'Q' => parse_cid_v0(&bytes)?,
+		_ => parse_cid_v1(&bytes)?,
+	};
+
+	let mut binary = Vec::with_capacity(cid.digest.len() + 8);
+	for value in [1, cid.codec, cid.hash, cid.digest.len() as u64] {
+		write_varint(value, &mut binary);
+	}
+	binary.extend_from_slice(cid.digest);
+	Some(binary)
+}
+
+/// Parses a bare sha2-256 multihash.
+fn parse_cid_v0(bytes: &[u8]) -> Option<Cid> {
+	let (hash, digest) = parse_multihash(bytes)?;
+	(hash == SHA2_256).then(|| Cid { codec: DAG_PB, hash, digest })
+}
+
+/// Parses `<version><codec><multihash>`.
+fn parse_cid_v1(bytes: &[u8]) -> Option<Cid> {
+	let (version, rest) = read_varint(bytes)?;
+	if version != 1 {
+		return None
+	}
+	let (codec, rest) = read_varint(rest)?;
+	let (hash, digest) = parse_multihash(rest)?;
+	Some(Cid { codec, hash, digest })
+}
+
+/// Parses `<hash code><digest length><digest>`, requiring the digest to fill the rest of `bytes`.
+fn parse_multihash(bytes: &[u8]) -> Option<(u64, &[u8])> {
+	let (code, rest) = read_varint(bytes)?;
+	let (length, digest) = read_varint(rest)?;
+	(length > 0 && length <= MAX_DIGEST_LEN && length == digest.len() as u64).then(|| (code, digest))
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+	while value >= 0x80 {
+		out.push(value as u8 | 0x80);
+		value >>= 7;
+	}
+	out.push(value as u8);
+}
+
+/// Reads an unsigned LEB128 varint of at most 9 bytes, as used by multiformats.
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+	let mut value = 0u64;
+	for (i, byte) in bytes.iter().enumerate().take(9) {
+		value |= u64::from(byte & 0x7f) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Some((value, &bytes[i + 1..]))
+		}
+	}
+	None
+}
+
+fn decode_base58(input: &[u8]) -> Option<Vec<u8>> {
+	// Little-endian big number, reversed at the end.
+	let mut bytes: Vec<u8> = Vec::new();
+	for c in input {
+		let mut carry = BASE58_ALPHABET.iter().position(|a| a == c)? as u32;
+		for byte in bytes.iter_mut() {
+			carry += u32::from(*byte) * 58;
+			*byte = carry as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			bytes.push(carry as u8);
+			carry >>= 8;
+		}
+	}
+	let zeros = input.iter().take_while(|c| **c == b'1').count();
+	bytes.extend(sp_std::iter::repeat(0).take(zeros));
+	bytes.reverse();
+	Some(bytes)
+}
+
+/// Decodes lowercase, unpadded RFC 4648 base32, the default multibase for CIDv1.
+fn decode_base32(input: &[u8]) -> Option<Vec<u8>> {
+	let mut bytes = Vec::with_capacity(input.len() * 5 / 8);
+	let mut buffer = 0u32;
+	let mut bits = 0u32;
+	for c in input {
+		let value = match c {
+			b'a'..=b'z' => c - b'a',
+			b'2'..=b'7' => c - b'2' + 26,
+			_ => return None,
+		};
+		buffer = (buffer << 5) | u32::from(value);
+		bits += 5;
+		if bits >= 8 {
+			bits -= 8;
+			bytes.push((buffer >> bits) as u8);
+			buffer &= (1 << bits) - 1;
+		}
+	}
+	Some(bytes)
+}
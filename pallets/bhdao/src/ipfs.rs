@@ -0,0 +1,71 @@
+//! Offchain retrieval of document content from an IPFS HTTP gateway.
+
+use crate::Availability;
+use sp_runtime::offchain::{http, storage::StorageValueRef, Duration};
+use sp_std::vec::Vec;
+
+/// Gateway used when the node operator did not configure one.
+pub const DEFAULT_GATEWAY: &[u8] = b"http://127.0.0.1:8080";
+
+/// Offchain local storage key holding the gateway URL, e.g. `http://127.0.0.1:8080`.
+pub const GATEWAY_KEY: &[u8] = b"bhdao::ipfs-gateway";
+
+const HTTP_TIMEOUT_MS: u64 = 10_000;
+
+/// The gateway configured on this node.
+pub fn gateway() -> Vec<u8> {
+	StorageValueRef::persistent(GATEWAY_KEY)
+		.get::<Vec<u8>>()
+		.ok()
+		.flatten()
+		.unwrap_or_else(|| DEFAULT_GATEWAY.to_vec())
+}
+
+/// Why a gateway check was inconclusive.
+#[derive(Debug)]
+pub enum Error {
+	Http(http::Error),
+	/// The gateway did not tell the size of the content.
+	MissingContentLength,
+}
+
+impl From<http::Error> for Error {
+	fn from(error: http::Error) -> Self {
+		Error::Http(error)
+	}
+}
+
+/// Asks `gateway` for the headers of `cid` and checks its size against `max_size` and its
+/// content type against the declared `format`. The content itself is not downloaded.
+pub fn check(gateway: &[u8], cid: &[u8], format: &[u8], max_size: u64) -> Result<Availability, Error> {
+	let mut url = gateway.strip_suffix(b"/").unwrap_or(gateway).to_vec();
+	url.extend_from_slice(b"/ipfs/");
+	url.extend_from_slice(cid);
+	let url = sp_std::str::from_utf8(&url).map_err(|_| http::Error::Unknown)?;
+
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+	let pending = http::Request::get(url)
+		.method(http::Method::Other("HEAD"))
+		.deadline(deadline)
+		.send()
+		.map_err(|_| http::Error::IoError)?;
+	let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+	if response.code != 200 {
+		return Ok(Availability::Unavailable)
+	}
+
+	let content_type = response.headers().find("content-type").map(|value| {
+		value.split(';').next().unwrap_or_default().trim().as_bytes().to_vec()
+	});
+	let size = response
+		.headers()
+		.find("content-length")
+		.and_then(|value| value.trim().parse::<u64>().ok())
+		.ok_or(Error::MissingContentLength)?;
+
+	Ok(match content_type {
+		_ if size > max_size => Availability::TooLarge,
+		Some(content_type) if content_type.as_slice() != format => Availability::FormatMismatch,
+		_ => Availability::Available { size },
+	})
+}
@@ -2,12 +2,31 @@
 
 pub use pallet::*;
 
+mod cid;
+pub mod ipfs;
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 
 #[cfg(test)]
 mod tests;
 
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of the keys the offchain worker signs availability attestations with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"bhda");
+
+pub mod sr25519 {
+	mod app_sr25519 {
+		use sp_runtime::app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, crate::KEY_TYPE);
+	}
+
+	pub type AuthoritySignature = app_sr25519::Signature;
+	pub type AuthorityId = app_sr25519::Public;
+}
+
 //#[cfg(feature = "runtime-benchmarks")]
 //mod benchmarking;
 
@@ -15,20 +34,25 @@ mod tests;
 pub mod pallet {
 	use frame_support::{
 		pallet_prelude::*,
-		traits::Currency,
+		storage::{with_transaction, TransactionOutcome},
+		traits::{Currency, Randomness, ReservableCurrency, Time},
+	};
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+		pallet_prelude::*,
 	};
-	use frame_system::pallet_prelude::*;
 	use scale_info::{
 		TypeInfo,
 	};
-	use sp_runtime::ArithmeticError;
+	use sp_runtime::{traits::{CheckedAdd, Zero}, ArithmeticError, PerThing, Percent};
 	use sp_std::vec::Vec;
 
 
 	#[cfg(feature = "std")]
 	use frame_support::serde::{Deserialize, Serialize};
 
-	//type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub(crate) type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub(crate) type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
 
 	#[pallet::type_value]
     pub fn DefaultQualificationVotingWindow<T: Config>() -> u32
@@ -54,6 +78,58 @@ pub mod pallet {
         0u32
     }
 
+	#[pallet::type_value]
+	pub fn DefaultDocumentFormats<T: Config>() -> Vec<Vec<u8>>
+	{
+		sp_std::vec![
+			b"application/pdf".to_vec(),
+			b"image/jpeg".to_vec(),
+			b"image/png".to_vec(),
+			b"image/tiff".to_vec(),
+			b"text/plain".to_vec(),
+			b"audio/mpeg".to_vec(),
+			b"video/mp4".to_vec(),
+		]
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultLicenses<T: Config>() -> Vec<Vec<u8>>
+	{
+		sp_std::vec![
+			b"CC0-1.0".to_vec(),
+			b"CC-BY-4.0".to_vec(),
+			b"CC-BY-SA-4.0".to_vec(),
+			b"CC-BY-NC-4.0".to_vec(),
+			b"CC-BY-NC-SA-4.0".to_vec(),
+			b"CC-BY-ND-4.0".to_vec(),
+			b"CC-BY-NC-ND-4.0".to_vec(),
+			b"PDM-1.0".to_vec(),
+		]
+	}
+
+	#[pallet::type_value]
+    pub fn DefaultApplicationVotingWindow<T: Config>() -> u32
+    {
+        14400u32
+    }
+
+	#[pallet::type_value]
+	pub fn DefaultApplicationQuorum<T: Config>() -> u32
+	{
+		1u32
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultRateLimits<T: Config>() -> RateLimits
+	{
+		RateLimits {
+			max_open_submissions: 10,
+			max_submissions_per_period: 10,
+			max_votes_created_per_period: 50,
+			period: 14400,
+		}
+	}
+
 	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Document<T:Config> {
@@ -74,6 +150,270 @@ pub mod pallet {
 		pub start: T::BlockNumber,
 		pub end: T::BlockNumber,
 		pub status: VoteStatus,
+		/// Index of the review stage this vote decides in the document's pipeline.
+		pub stage: u32,
+		/// Role whose members may vote.
+		pub role: Roles,
+		/// Minimum number of ballots for the vote to count.
+		pub quorum: u32,
+		/// Share of the ballots that `yes` votes must exceed to pass.
+		pub threshold: Percent,
+	}
+
+	/// One stage of a document review pipeline.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct ReviewStage {
+		/// Role whose members vote in this stage.
+		pub role: Roles,
+		/// Length of the vote in blocks.
+		pub window: u32,
+		/// Minimum number of ballots for the vote to count.
+		pub quorum: u32,
+		/// Share of the ballots that `yes` votes must exceed to pass.
+		pub threshold: Percent,
+	}
+
+	/// Upper bound on the number of stages in a review pipeline.
+	pub const MAX_PIPELINE_STAGES: usize = 8;
+
+	/// Maximum length of a member's display name in bytes.
+	pub const MAX_DISPLAY_NAME_LEN: usize = 64;
+
+	/// Maximum length of a member's affiliation in bytes.
+	pub const MAX_AFFILIATION_LEN: usize = 128;
+
+	/// Longest delegation chain that is followed when counting delegated votes.
+	pub const MAX_DELEGATION_DEPTH: u32 = 8;
+
+	/// Availability checks the offchain worker runs per block.
+	pub const MAX_AVAILABILITY_CHECKS_PER_BLOCK: usize = 5;
+
+	/// Blocks between two availability checks of a document without an attestation.
+	pub const AVAILABILITY_RETRY_BLOCKS: u32 = 10;
+
+	/// Failed availability checks after which a document is no longer retried.
+	pub const MAX_AVAILABILITY_ATTEMPTS: u32 = 5;
+
+	/// Maximum length of a textual review note in bytes.
+	pub const MAX_REVIEW_NOTE_LEN: usize = 512;
+
+	/// Maximum length of a category name in bytes.
+	pub const MAX_CATEGORY_NAME_LEN: usize = 64;
+
+	/// Maximum number of tags on a document.
+	pub const MAX_TAGS_PER_DOCUMENT: usize = 8;
+
+	/// Maximum length of a tag in bytes.
+	pub const MAX_TAG_LEN: usize = 32;
+
+	/// Maximum length of a license identifier in bytes.
+	pub const MAX_LICENSE_LEN: usize = 64;
+
+	/// Maximum number of licenses governance may allow.
+	pub const MAX_LICENSES: usize = 64;
+
+	/// Maximum length of the rights holder of a document in bytes.
+	pub const MAX_RIGHTS_HOLDER_LEN: usize = 128;
+
+	/// Maximum number of provenance entries on a document.
+	pub const MAX_PROVENANCE_ENTRIES: usize = 16;
+
+	/// Maximum length of a provenance entry in bytes.
+	pub const MAX_PROVENANCE_ENTRY_LEN: usize = 256;
+
+	/// Maximum number of documents returned by one page of a document index query.
+	pub const MAX_DOCUMENTS_PER_PAGE: u32 = 100;
+
+	/// Maximum number of documents in one batched vote creation.
+	pub const MAX_VOTES_PER_BATCH: usize = 32;
+
+	/// How far ahead, in blocks, a vote can be scheduled to open.
+	pub const MAX_VOTE_START_DELAY: u32 = 100_800;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	/// Per-account limits on document submissions and vote creation.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct RateLimits {
+		/// Documents an account may have that are neither verified nor rejected.
+		pub max_open_submissions: u32,
+		/// Documents an account may submit within one period.
+		pub max_submissions_per_period: u32,
+		/// Qualification and verification votes an account may open within one period.
+		pub max_votes_created_per_period: u32,
+		/// Length of a rate limiting period in blocks.
+		pub period: u32,
+	}
+
+	/// Activity of an account counted against the [`RateLimits`].
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Default)]
+	pub struct AccountActivity<BlockNumber> {
+		pub open_submissions: u32,
+		/// Block at which the current rate limiting period started.
+		pub period_start: BlockNumber,
+		pub submissions_in_period: u32,
+		pub votes_created_in_period: u32,
+	}
+
+	/// Request of an account to join a role, decided by the role's current members.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Application<T:Config> {
+		pub applicant: T::AccountId,
+		pub role: Roles,
+		/// IPFS CID of the applicant's credentials.
+		pub profile: Vec<u8>,
+		/// Amount reserved from the applicant until the application is decided.
+		pub bond: BalanceOf<T>,
+		pub yes_votes: u64,
+		pub no_votes: u64,
+		pub start: T::BlockNumber,
+		pub end: T::BlockNumber,
+		pub status: VoteStatus,
+	}
+
+	/// Self-declared identity of a member, shown by UIs next to its account.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct MemberProfile<T:Config> {
+		pub display_name: Vec<u8>,
+		pub affiliation: Vec<u8>,
+		/// IPFS CID of the member's full profile.
+		pub profile_cid: Vec<u8>,
+		/// Amount reserved from the member while the profile is stored.
+		pub deposit: BalanceOf<T>,
+	}
+
+	/// When an account joined a role and the uid it was given.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Membership<T:Config> {
+		pub uid: u32,
+		pub joined_at: T::BlockNumber,
+	}
+
+	/// How many document votes in a row a member may miss before being flagged as inactive, and
+	/// how long it then has to vote again before losing its role.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct InactivityPolicy {
+		pub max_missed_votes: u32,
+		/// Blocks between being flagged and being suspended.
+		pub grace_period: u32,
+	}
+
+	/// Participation of a member in the votes of one of its roles.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Default)]
+	pub struct Participation<BlockNumber> {
+		/// Last block at which the member voted, directly or through a delegate.
+		pub last_active: BlockNumber,
+		/// Document votes missed since the member last voted.
+		pub missed_votes: u32,
+		/// Block at which a member flagged as inactive will be suspended.
+		pub suspend_at: Option<BlockNumber>,
+	}
+
+	/// Final tally of a document vote whose records were pruned.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	pub struct VoteSummary {
+		pub vote_type: VoteType,
+		pub voting_id: u64,
+		pub stage: u32,
+		pub yes_votes: u64,
+		pub no_votes: u64,
+		pub outcome: VoteStatus,
+	}
+
+	/// When something happened, as the block number and the timestamp of that block.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct RecordedAt<T:Config> {
+		pub block: T::BlockNumber,
+		pub time: MomentOf<T>,
+	}
+
+	/// When a document was created, last changed and verified. Documents submitted before
+	/// timelines were recorded show block 0 for the events they predate.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct DocumentTimeline<T:Config> {
+		pub created: RecordedAt<T>,
+		/// Last change of the document's status, classification or rights.
+		pub updated: RecordedAt<T>,
+		/// Set once the document is verified.
+		pub verified: Option<RecordedAt<T>>,
+	}
+
+	/// Who finalized a vote, and when.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Finalization<T:Config> {
+		pub finalized: RecordedAt<T>,
+		pub finalizer: T::AccountId,
+	}
+
+	/// Licensing and rights information of a document, locked once it is verified.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct RightsMetadata<T:Config> {
+		/// SPDX identifier of the license, e.g. `CC-BY-4.0`, from the governance license list.
+		pub license: Vec<u8>,
+		/// Person or institution holding the rights, of at most [`MAX_RIGHTS_HOLDER_LEN`] bytes.
+		pub rights_holder: Vec<u8>,
+		/// Custody history of the item, oldest first.
+		pub provenance: Vec<Vec<u8>>,
+		/// Block until which the content may not be used.
+		pub embargo_until: Option<T::BlockNumber>,
+	}
+
+	/// Reasoning a voter attaches to its ballot.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ReviewNote {
+		/// A short note of at most [`MAX_REVIEW_NOTE_LEN`] bytes.
+		Text(Vec<u8>),
+		/// IPFS CID of a full review, of at most [`crate::cid::MAX_CID_LEN`] bytes.
+		Cid(Vec<u8>),
+	}
+
+	/// Result of fetching a document's content from IPFS.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum Availability {
+		Available { size: u64 },
+		Unavailable,
+		/// The gateway served a content type other than the document's declared format.
+		FormatMismatch,
+		TooLarge,
+	}
+
+	/// Latest availability check of a document's content.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct AvailabilityAttestation<T:Config> {
+		pub availability: Availability,
+		pub attester: T::AccountId,
+		pub attested_at: T::BlockNumber,
+	}
+
+	/// How qualification votes draw a random panel of reviewers.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct ReviewPanelSettings {
+		/// Number of qualifiers drawn for each panel.
+		pub size: u32,
+		/// Share of the drawn panel that must vote for the result to count.
+		pub quorum: Percent,
+	}
+
+	/// Qualifiers drawn to review a document, and the votes needed from them.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ReviewPanel<T:Config> {
+		pub members: Vec<T::AccountId>,
+		pub quorum: u32,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
@@ -91,6 +431,7 @@ pub mod pallet {
 		Passed,
 		Failed,
 		Expired,
+		Cancelled,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq, Copy)]
@@ -110,17 +451,51 @@ pub mod pallet {
 		Qualification,
 		Verification,
 		Proposal,
+		Application,
 	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config +pallet_nft::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config +pallet_nft::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type Currency: Currency<Self::AccountId>;
+		/// Crypto of the keys the offchain worker signs availability attestations with. The
+		/// accounts of these keys must be qualifiers.
+		type AppCrypto: AppCrypto<Self::Public, Self::Signature>;
+		/// Number of ballots of a single vote that may carry a review note.
+		#[pallet::constant]
+		type MaxReviewNotesPerVote: Get<u32>;
+		/// Largest document content, in bytes, the availability check accepts.
+		#[pallet::constant]
+		type MaxDocumentSize: Get<u64>;
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Amount reserved from an account applying for a role.
+		#[pallet::constant]
+		type ApplicationBond: Get<BalanceOf<Self>>;
+		/// Amount reserved from a member while it has a profile.
+		#[pallet::constant]
+		type ProfileDeposit: Get<BalanceOf<Self>>;
+		/// Supply cap of the qualifier role collection.
+		#[pallet::constant]
+		type MaxQualifiers: Get<u32>;
+		/// Supply cap of the collector role collection.
+		#[pallet::constant]
+		type MaxCollectors: Get<u32>;
+		/// Supply cap of the contributor role collection.
+		#[pallet::constant]
+		type MaxContributors: Get<u32>;
+		/// Blocks a finished document vote is kept in full before its ballots are cleared and the
+		/// vote is reduced to a summary on the document.
+		#[pallet::constant]
+		type VoteRetentionPeriod: Get<Self::BlockNumber>;
+		/// Source of the timestamps recorded alongside block numbers.
+		type Time: Time;
+		/// Source of randomness used to draw review panels.
+		type Randomness: Randomness<Option<Self::Hash>, Self::BlockNumber>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -182,6 +557,20 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn get_rate_limits)]
+	pub(super) type Limits<T> = StorageValue<_, RateLimits, ValueQuery, DefaultRateLimits<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_account_activity)]
+	pub(super) type AccountActivities<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		AccountActivity<T::BlockNumber>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn get_document)]
 	pub(super) type Documents<T:Config> = StorageMap<
@@ -192,6 +581,23 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Document id registered for the binary CIDv1 of each content hash, so the same content is
+	/// only submitted once whatever the CID version it is given in.
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_by_hash)]
+	pub(super) type DocumentsByHash<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		u64,
+		OptionQuery,
+	>;
+
+	/// MIME types accepted as a document `format`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_formats)]
+	pub(super) type DocumentFormats<T:Config> = StorageValue<_, Vec<Vec<u8>>, ValueQuery, DefaultDocumentFormats<T>>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn get_qualification_vote)]
 	pub(super) type QualificationVotes<T:Config> = StorageMap<
@@ -212,536 +618,2771 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Qualification and verification vote ids opened for each document, oldest first. Pruned
+	/// votes stay listed; their outcome is kept in [`VoteSummaries`].
 	#[pallet::storage]
-	#[pallet::getter(fn get_member_vote)]
-	pub(super) type MemberVote<T:Config> = StorageMap<
+	#[pallet::getter(fn get_document_votes)]
+	pub(super) type DocumentVotes<T:Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
-		(T::AccountId,VoteType,u64),
-		bool,
-		OptionQuery,
+		u64,
+		Blake2_128Concat,
+		VoteType,
+		Vec<u64>,
+		ValueQuery,
 	>;
 
+	/// When set, qualification votes are restricted to a randomly drawn panel of qualifiers.
 	#[pallet::storage]
-	#[pallet::getter(fn get_all_qualifiers)]
-	pub(super) type Qualifiers<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	#[pallet::getter(fn get_review_panel_settings)]
+	pub(super) type ReviewPanelConfig<T> = StorageValue<_, ReviewPanelSettings, OptionQuery>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn get_all_collectors)]
-	pub(super) type Collectors<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	#[pallet::getter(fn get_review_panel)]
+	pub(super) type ReviewPanels<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		ReviewPanel<T>,
+		OptionQuery,
+	>;
 
+	/// Review pipelines for document formats that do not use the default pipeline.
 	#[pallet::storage]
-	#[pallet::getter(fn get_all_contributors)]
-	pub(super) type Contributors<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
-
-
-	// Pallets use events to inform users when important changes are made.
-	// https://docs.substrate.io/v3/runtime/events-and-errors
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
-		QualifierAdded(T::AccountId,u32),
-		CollectorAdded(T::AccountId,u32),
-		ContributorAdded(T::AccountId,u32),
-		DocumentCreated(T::AccountId,u64),
-		DocumentStatusUpdated(u64,u8),
-		QualificationVotingWindowChanged(u32),
-		QualificationVotingStarted(u64),
-		VerificationVotingWindowChanged(u32),
-		VerificationVotingStarted(u64),
-		QualificationVotingEnded(u64),
-		VerificationVotingEnded(u64),
-		QualificationQuorumChanged(u32),
-		VerificationQuorumChanged(u32),
-		VoteCast(u8,u64),
-	}
+	#[pallet::getter(fn get_pipeline)]
+	pub(super) type Pipelines<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Vec<ReviewStage>,
+		OptionQuery,
+	>;
 
-	// Errors inform users that something went wrong.
-	#[pallet::error]
-	pub enum Error<T> {
-		QualifierAlreadyExists,
-		CollectorAlreadyExists,
-		ContributorAlreadyExists,
-		NotAQualifier,
-		NotACollector,
-		NotAContributor,
-		NotAuthorized,
-		DocumentNotFound,
-		IncorrectDocumentStatus,
-		DocumentTitleNotProvided,
-		DocumentDescriptionNotProvided,
-		DocumentFormatNotProvided,
-		DocumentIPFSHashNotProvided,
-		VerificationVoteAlreadyCreated,
-		VotingWindowNotValid,
-		DocumentNotReviewed,
-		VoteNotFound,
-		VoteNotInProgress,
-		VoteStillInProgress,
-		DocumentNotUnderReview,
-		MemberAlreadyVoted,
-	}
+	/// Index of the pipeline stage each document is in, or enters next.
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_stage)]
+	pub(super) type DocumentStages<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		u32,
+		ValueQuery,
+	>;
 
-	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
-	// These functions materialize as "extrinsics", which are often compared to transactions.
-	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000)]
-		pub fn init_collections(origin: OriginFor<T>) -> DispatchResult {
-			ensure_root(origin.clone())?;
-			let max_qualifiers: u32 = 200;
-			let max_collectors : u32 = 100;
-			let max_contributors : u32 = 1000;
+	/// Member each account delegated its vote to, per vote type.
+	#[pallet::storage]
+	#[pallet::getter(fn get_delegation)]
+	pub(super) type Delegations<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		VoteType,
+		T::AccountId,
+		OptionQuery,
+	>;
 
-			// create qualifiers collection
-			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::QualifierRole as u32,max_qualifiers,b"Qualifiers".to_vec()).ok();
+	/// Accounts that delegated their vote to each member, per vote type.
+	#[pallet::storage]
+	#[pallet::getter(fn get_delegators)]
+	pub(super) type Delegators<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		VoteType,
+		Vec<T::AccountId>,
+		ValueQuery,
+	>;
 
-			//create collectors collection
-			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::CollectorRole as u32,max_collectors,b"Collectors".to_vec()).ok();
+	#[pallet::storage]
+	#[pallet::getter(fn get_application_voting_window)]
+	pub(super) type ApplicationVotingWindow<T> = StorageValue<_, u32,ValueQuery,DefaultApplicationVotingWindow<T>>;
 
-			//create contributors collection
-			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::ContributorRole as u32,max_contributors,b"Contributors".to_vec()).ok();
+	/// Minimum number of ballots for an application to be decided.
+	#[pallet::storage]
+	#[pallet::getter(fn get_application_quorum)]
+	pub(super) type ApplicationQuorum<T> = StorageValue<_, u32,ValueQuery,DefaultApplicationQuorum<T>>;
 
-			Ok(())
-		}
+	/// Quorum each application was opened with.
+	#[pallet::storage]
+	#[pallet::getter(fn get_quorum_of_application)]
+	pub(super) type ApplicationQuorums<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		u32,
+		OptionQuery,
+	>;
 
-		#[pallet::weight(10_000)]
-		pub fn add_qualifier(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			ensure_root(origin.clone())?;
-			let mut qualifiers = Qualifiers::<T>::get();
+	#[pallet::storage]
+	#[pallet::getter(fn get_application_count)]
+	pub(super) type ApplicationsCount<T> = StorageValue<_, u64,ValueQuery>;
 
-			let uid = Self::qualifiers_uid_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+	#[pallet::storage]
+	#[pallet::getter(fn get_application)]
+	pub(super) type Applications<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Application<T>,
+		OptionQuery,
+	>;
 
-			match qualifiers.binary_search(&who) {
-				Ok(_) => Err(Error::<T>::QualifierAlreadyExists.into()),
-				Err(index) => {
-					qualifiers.insert(index, who.clone());
-					Qualifiers::<T>::put(qualifiers);
-					QualifiersCount::<T>::put(uid.clone());
-					//mint NFT
-					pallet_nft::Pallet::<T>::mint(origin.clone(),Roles::QualifierRole as u32,who.clone()).ok();
-					Self::deposit_event(Event::QualifierAdded(who,uid));
-					Ok(())
-				}
-			}
+	/// Undecided application of each account, per role.
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending_application)]
+	pub(super) type PendingApplications<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		Roles,
+		u64,
+		OptionQuery,
+	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn get_member_vote)]
+	pub(super) type MemberVote<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId,VoteType,u64),
+		bool,
+		OptionQuery,
+	>;
+
+	/// Voters of each document vote, so that their ballots can be pruned.
+	#[pallet::storage]
+	pub(super) type VoteBallots<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(VoteType,u64),
+		Vec<T::AccountId>,
+		ValueQuery,
+	>;
+
+	/// Finished document votes waiting to be pruned, in the order they finished, with the block
+	/// from which they can be.
+	#[pallet::storage]
+	pub(super) type PruneQueue<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		(T::BlockNumber, VoteType, u64),
+		OptionQuery,
+	>;
+
+	/// Index of the oldest and one past the newest entry of the [`PruneQueue`].
+	#[pallet::storage]
+	pub(super) type PruneQueueBounds<T> = StorageValue<_, (u64, u64), ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vote_summaries)]
+	pub(super) type VoteSummaries<T> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		Vec<VoteSummary>,
+		ValueQuery,
+	>;
+
+	/// Timeline of each document.
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_timeline)]
+	pub(super) type DocumentTimelines<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		DocumentTimeline<T>,
+		OptionQuery,
+	>;
+
+	/// Who finalized each document vote and role application, and when.
+	#[pallet::storage]
+	#[pallet::getter(fn get_finalization)]
+	pub(super) type Finalizations<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(VoteType,u64),
+		Finalization<T>,
+		OptionQuery,
+	>;
+
+	/// License identifiers documents may be published under, at most [`MAX_LICENSES`].
+	#[pallet::storage]
+	#[pallet::getter(fn get_licenses)]
+	pub(super) type Licenses<T:Config> = StorageValue<_, Vec<Vec<u8>>, ValueQuery, DefaultLicenses<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_rights)]
+	pub(super) type DocumentRights<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		RightsMetadata<T>,
+		OptionQuery,
+	>;
+
+	/// Governance-defined document categories, by id.
+	#[pallet::storage]
+	#[pallet::getter(fn get_category)]
+	pub(super) type Categories<T> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_categories_count)]
+	pub(super) type CategoriesCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_category)]
+	pub(super) type DocumentCategories<T> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		u32,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_document_tags)]
+	pub(super) type DocumentTags<T> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		Vec<Vec<u8>>,
+		ValueQuery,
+	>;
+
+	/// Index of documents by category and status, so that e.g. the verified documents of a
+	/// category can be paged through by key prefix.
+	#[pallet::storage]
+	pub(super) type DocumentsByCategory<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, u32>,
+			NMapKey<Twox64Concat, DocumentStatus>,
+			NMapKey<Twox64Concat, u64>,
+		),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type DocumentsByTag<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Twox64Concat,
+		u64,
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type DocumentsByCreator<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		u64,
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type DocumentsByStatus<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		DocumentStatus,
+		Twox64Concat,
+		u64,
+		(),
+		OptionQuery,
+	>;
+
+	/// Review notes voters attached to their ballots.
+	#[pallet::storage]
+	#[pallet::getter(fn get_review_note)]
+	pub(super) type ReviewNotes<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId,VoteType,u64),
+		ReviewNote,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_review_notes_count)]
+	pub(super) type ReviewNotesCount<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(VoteType,u64),
+		u32,
+		ValueQuery,
+	>;
+
+	/// Declared affiliations between members. An affiliation in either direction bars both
+	/// accounts from voting on or finalizing votes for each other's documents.
+	#[pallet::storage]
+	#[pallet::getter(fn get_affiliation)]
+	pub(super) type Affiliations<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_member_profile)]
+	pub(super) type MemberProfiles<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		MemberProfile<T>,
+		OptionQuery,
+	>;
+
+	/// Role memberships. Members added before memberships were recorded show the uid of their
+	/// badge and block 0.
+	#[pallet::storage]
+	#[pallet::getter(fn get_membership)]
+	pub(super) type Memberships<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		Roles,
+		Membership<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_inactivity_policy)]
+	pub(super) type Inactivity<T> = StorageValue<_, InactivityPolicy, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_participation)]
+	pub(super) type Participations<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		Roles,
+		Participation<T::BlockNumber>,
+		ValueQuery,
+	>;
+
+	/// Members flagged as inactive, by the block at which their grace period ends.
+	#[pallet::storage]
+	#[pallet::getter(fn get_suspensions_due)]
+	pub(super) type SuspensionsDue<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Vec<(T::AccountId, Roles)>,
+		ValueQuery,
+	>;
+
+	/// Documents under review whose content the offchain worker has not attested yet, with the
+	/// block they entered review.
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending_availability)]
+	pub(super) type PendingAvailability<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Failed availability checks of a pending document.
+	#[pallet::storage]
+	#[pallet::getter(fn get_availability_attempts)]
+	pub(super) type AvailabilityAttempts<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		u32,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_availability)]
+	pub(super) type Availabilities<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		AvailabilityAttestation<T>,
+		OptionQuery,
+	>;
+
+	/// Whether documents need an `Available` attestation to be verified.
+	#[pallet::storage]
+	#[pallet::getter(fn is_availability_required)]
+	pub(super) type AvailabilityRequired<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// Whether the DAO is halted. The runtime call filter rejects signed bhdao and nft calls
+	/// while this is set; governance acts through root, which the filter does not apply to.
+	#[pallet::storage]
+	#[pallet::getter(fn is_paused)]
+	pub(super) type Paused<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// Block the DAO was paused at, to move membership deadlines past the pause on resume.
+	#[pallet::storage]
+	#[pallet::getter(fn get_paused_since)]
+	pub(super) type PausedSince<T:Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Length of a qualifier term in blocks. Zero means qualifier seats do not expire.
+	#[pallet::storage]
+	#[pallet::getter(fn get_qualifier_term)]
+	pub(super) type QualifierTerm<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Block at which each qualifier's current term ends.
+	#[pallet::storage]
+	#[pallet::getter(fn get_qualifier_term_end)]
+	pub(super) type QualifierTermEnds<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Qualifiers whose term was scheduled to end at each block. Entries of renewed terms are
+	/// skipped when the block is reached.
+	#[pallet::storage]
+	#[pallet::getter(fn get_term_expiries)]
+	pub(super) type TermExpiries<T:Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Vec<T::AccountId>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_all_qualifiers)]
+	pub(super) type Qualifiers<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_all_collectors)]
+	pub(super) type Collectors<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_all_contributors)]
+	pub(super) type Contributors<T:Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+
+	// Pallets use events to inform users when important changes are made.
+	// https://docs.substrate.io/v3/runtime/events-and-errors
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A qualifier was added and given the role uid.
+		QualifierAdded { who: T::AccountId, uid: u32 },
+		/// A collector was added and given the role uid.
+		CollectorAdded { who: T::AccountId, uid: u32 },
+		/// A contributor was added and given the role uid.
+		ContributorAdded { who: T::AccountId, uid: u32 },
+		/// A contributor submitted a new document.
+		DocumentCreated { creator: T::AccountId, document_id: u64 },
+		/// A document moved from one status to another.
+		DocumentStatusUpdated { document_id: u64, from: DocumentStatus, to: DocumentStatus },
+		/// The qualification voting window was changed.
+		QualificationVotingWindowChanged { window: u32 },
+		/// A qualification vote was created for a document, taking ballots from `start` until `end`.
+		QualificationVotingStarted { voting_id: u64, document_id: u64, start: T::BlockNumber, end: T::BlockNumber },
+		/// A document of a batch could not be put to a qualification vote.
+		QualificationVotingNotCreated { document_id: u64, error: DispatchError },
+		/// A batch of qualification votes was processed.
+		QualificationVotingsCreated { created: u32, failed: u32 },
+		/// The verification voting window was changed.
+		VerificationVotingWindowChanged { window: u32 },
+		/// A verification vote was created for a document, taking ballots from `start` until `end`.
+		VerificationVotingStarted { voting_id: u64, document_id: u64, start: T::BlockNumber, end: T::BlockNumber },
+		/// A qualification vote was finalized with its final tally and outcome.
+		QualificationVotingEnded {
+			voting_id: u64,
+			document_id: u64,
+			yes_votes: u64,
+			no_votes: u64,
+			outcome: VoteStatus,
+		},
+		/// A verification vote was finalized with its final tally and outcome.
+		VerificationVotingEnded {
+			voting_id: u64,
+			document_id: u64,
+			yes_votes: u64,
+			no_votes: u64,
+			outcome: VoteStatus,
+		},
+		/// The qualification quorum was changed.
+		QualificationQuorumChanged { quorum: u32 },
+		/// The verification quorum was changed.
+		VerificationQuorumChanged { quorum: u32 },
+		/// A MIME type was added to the allowed document formats.
+		DocumentFormatAdded { format: Vec<u8> },
+		/// A MIME type was removed from the allowed document formats.
+		DocumentFormatRemoved { format: Vec<u8> },
+		/// The per-account rate limits were changed.
+		RateLimitsChanged { limits: RateLimits },
+		/// A member declared an affiliation with another account.
+		AffiliationDeclared { who: T::AccountId, with: T::AccountId },
+		/// A declared affiliation was removed.
+		AffiliationRemoved { who: T::AccountId, with: T::AccountId },
+		/// Review panels were enabled, changed or disabled.
+		ReviewPanelSettingsChanged { settings: Option<ReviewPanelSettings> },
+		/// A panel of qualifiers was drawn for a qualification vote.
+		ReviewPanelDrawn { voting_id: u64, members: Vec<T::AccountId>, quorum: u32 },
+		/// A review pipeline was configured for a document format.
+		PipelineSet { format: Vec<u8>, stages: Vec<ReviewStage> },
+		/// A document format went back to the default review pipeline.
+		PipelineRemoved { format: Vec<u8> },
+		/// A member delegated its votes of a vote type to another member.
+		DelegationSet { delegator: T::AccountId, vote_type: VoteType, delegate: T::AccountId },
+		/// A member took back its votes of a vote type.
+		DelegationRevoked { delegator: T::AccountId, vote_type: VoteType },
+		/// Ballots of members who delegated their vote were added to a vote's tally.
+		DelegatedVotesCounted { vote_type: VoteType, voting_id: u64, yes_votes: u64, no_votes: u64 },
+		/// An account applied for a role.
+		RoleApplicationSubmitted { application_id: u64, applicant: T::AccountId, role: Roles, end: T::BlockNumber },
+		/// A role application was decided; admitted applicants received the role.
+		RoleApplicationDecided { application_id: u64, yes_votes: u64, no_votes: u64, outcome: VoteStatus },
+		/// An applicant withdrew its application.
+		RoleApplicationWithdrawn { application_id: u64 },
+		/// The application voting window was changed.
+		ApplicationVotingWindowChanged { window: u32 },
+		/// The application quorum was changed.
+		ApplicationQuorumChanged { quorum: u32 },
+		/// A member created or updated its profile.
+		ProfileSet { who: T::AccountId },
+		/// A member removed its profile and got its deposit back.
+		ProfileCleared { who: T::AccountId },
+		/// A member lost its role.
+		MemberRemoved { who: T::AccountId, role: Roles },
+		/// The qualifier term length was changed.
+		QualifierTermChanged { term: u32 },
+		/// A qualifier was re-elected for another term.
+		QualifierTermRenewed { who: T::AccountId, ends_at: Option<T::BlockNumber> },
+		/// A qualifier's term ended without renewal and its seat was removed.
+		QualifierTermExpired { who: T::AccountId },
+		/// The inactivity policy was changed. `None` disables inactivity tracking.
+		InactivityPolicyChanged { policy: Option<InactivityPolicy> },
+		/// A member missed too many votes and will be suspended at `suspend_at` unless it votes again.
+		MemberFlaggedInactive { who: T::AccountId, role: Roles, missed_votes: u32, suspend_at: T::BlockNumber },
+		/// A flagged member voted again within its grace period.
+		MemberReactivated { who: T::AccountId, role: Roles },
+		/// A flagged member did not vote within its grace period and lost its role.
+		MemberSuspended { who: T::AccountId, role: Roles },
+		/// Governance moved the end of an in-flight vote from `from` to `to`.
+		VoteExtended { vote_type: VoteType, voting_id: u64, from: T::BlockNumber, to: T::BlockNumber },
+		/// Governance cancelled an in-flight vote.
+		VoteCancelled { vote_type: VoteType, voting_id: u64 },
+		/// A qualifier's offchain worker reported whether a document's content can be retrieved.
+		/// `replaced` is the earlier, inconclusive result this one overrides.
+		AvailabilityAttested { document_id: u64, attester: T::AccountId, availability: Availability, replaced: Option<Availability> },
+		/// Verification now does or does not need an `Available` attestation.
+		AvailabilityRequirementChanged { required: bool },
+		/// Governance allowed documents to be published under `license`.
+		LicenseAdded { license: Vec<u8> },
+		/// Governance stopped accepting `license` for new rights metadata.
+		LicenseRemoved { license: Vec<u8> },
+		/// The creator of a document set its rights metadata.
+		DocumentRightsSet { document_id: u64, license: Vec<u8>, embargo_until: Option<T::BlockNumber> },
+		/// Governance added a category documents can be filed under.
+		CategoryAdded { category_id: u32, name: Vec<u8> },
+		/// Governance removed an empty category.
+		CategoryRemoved { category_id: u32 },
+		/// The creator of a document set its category and tags.
+		DocumentClassified { document_id: u64, category: Option<u32>, tags: Vec<Vec<u8>> },
+		/// The records of a finished document vote were pruned, leaving a summary on the document.
+		VotePruned { vote_type: VoteType, voting_id: u64, document_id: u64 },
+		/// The DAO was paused or resumed.
+		PauseChanged { paused: bool },
+		/// A member cast a ballot in a vote, optionally explaining it with a review note.
+		VoteCast { voter: T::AccountId, vote_type: VoteType, voting_id: u64, aye: bool, note: Option<ReviewNote> },
+		/// A vote passed, but the document lacked the required `Available` attestation and was
+		/// rejected instead of verified.
+		DocumentRejectedAsUnavailable { document_id: u64 },
+		/// A passed application could not be admitted, e.g. because the role is full. The bond
+		/// was returned and the application expired.
+		ApplicantNotAdmitted { application_id: u64, error: DispatchError },
+	}
+
+	// Errors inform users that something went wrong.
+	#[pallet::error]
+	pub enum Error<T> {
+		QualifierAlreadyExists,
+		CollectorAlreadyExists,
+		ContributorAlreadyExists,
+		NotAQualifier,
+		NotACollector,
+		NotAContributor,
+		NotAuthorized,
+		DocumentNotFound,
+		IncorrectDocumentStatus,
+		DocumentTitleNotProvided,
+		DocumentDescriptionNotProvided,
+		DocumentFormatNotProvided,
+		DocumentIPFSHashNotProvided,
+		VerificationVoteAlreadyCreated,
+		VotingWindowNotValid,
+		DocumentNotReviewed,
+		VoteNotFound,
+		VoteNotInProgress,
+		VoteStillInProgress,
+		DocumentNotUnderReview,
+		MemberAlreadyVoted,
+		QualificationVoteAlreadyCreated,
+		DocumentNotSubmitted,
+		DocumentNotInVerification,
+		DocumentNotUnderVote,
+		DocumentAlreadyFinalized,
+		InvalidDocumentStatusTransition,
+		VoteNotCurrent,
+		InvalidDocumentHash,
+		DocumentFormatNotAllowed,
+		DocumentAlreadySubmitted,
+		DocumentFormatAlreadyAllowed,
+		DocumentFormatNotFound,
+		TooManyOpenSubmissions,
+		SubmissionRateLimitExceeded,
+		VoteCreationRateLimitExceeded,
+		InvalidRateLimits,
+		ConflictOfInterest,
+		AffiliationAlreadyDeclared,
+		AffiliationNotFound,
+		InvalidReviewPanelSettings,
+		NoEligibleReviewers,
+		NotAPanelMember,
+		ReviewStageNotFound,
+		InvalidPipeline,
+		PipelineNotFound,
+		NotAMember,
+		CannotDelegateToSelf,
+		DelegateRoleMismatch,
+		DelegationCycle,
+		DelegationChainTooLong,
+		DelegationNotFound,
+		AlreadyAMember,
+		ApplicationAlreadyPending,
+		ApplicationNotFound,
+		ApplicationNotInProgress,
+		InvalidProfile,
+		DisplayNameTooLong,
+		AffiliationTooLong,
+		ProfileNotFound,
+		NoTermToRenew,
+		InvalidInactivityPolicy,
+		DocumentNotAvailable,
+		ReviewNoteTooLong,
+		InvalidReviewNote,
+		TooManyReviewNotes,
+		InvalidCategoryName,
+		CategoryNotFound,
+		CategoryNotEmpty,
+		InvalidTag,
+		DuplicateTag,
+		TooManyTags,
+		VoteStartInPast,
+		VoteStartTooFar,
+		InvalidVoteType,
+		TooManyDocuments,
+		LicenseNotAllowed,
+		LicenseAlreadyAllowed,
+		LicenseNotFound,
+		RightsHolderTooLong,
+		InvalidProvenance,
+		RightsLocked,
+		AvailabilityNotPending,
+		PipelineInUse,
+		TooManyMembers,
+		LicenseTooLong,
+		TooManyLicenses,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// Members cannot act during a pause; deadlines falling in it are moved on resume.
+			if Self::is_paused() {
+				return T::DbWeight::get().reads(1)
+			}
+			Self::expire_qualifier_terms(now).saturating_add(Self::suspend_inactive_members(now))
+		}
+
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::prune_finished_votes(now, remaining_weight)
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			Self::attest_pending_availability(now);
+		}
+	}
+
+	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
+	// These functions materialize as "extrinsics", which are often compared to transactions.
+	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(10_000)]
+		pub fn init_collections(origin: OriginFor<T>) -> DispatchResult {
+			ensure_root(origin.clone())?;
+			let max_qualifiers = T::MaxQualifiers::get();
+			let max_collectors = T::MaxCollectors::get();
+			let max_contributors = T::MaxContributors::get();
+
+			// create qualifiers collection
+			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::QualifierRole as u32,max_qualifiers,b"Qualifiers".to_vec(),false).ok();
+
+			//create collectors collection
+			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::CollectorRole as u32,max_collectors,b"Collectors".to_vec(),false).ok();
+
+			//create contributors collection
+			pallet_nft::Pallet::<T>::create_collection(origin.clone(),Roles::ContributorRole as u32,max_contributors,b"Contributors".to_vec(),false).ok();
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn add_qualifier(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_add_member(who, Roles::QualifierRole)
+		}
+
+		#[pallet::weight(10_000 )]
+		pub fn add_collector(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_add_member(who, Roles::CollectorRole)
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn add_contributor(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_add_member(who, Roles::ContributorRole)
+		}
+
+
+		#[pallet::weight(10_000)]
+		pub fn create_document(origin: OriginFor<T>, title: Vec<u8>, description: Vec<u8>,
+		format: Vec<u8>, hash: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::ensure_contributor(who.clone()),Error::<T>::NotAContributor);
+			ensure!(!title.is_empty(),Error::<T>::DocumentTitleNotProvided);
+			ensure!(!description.is_empty(),Error::<T>::DocumentDescriptionNotProvided);
+			ensure!(!format.is_empty(),Error::<T>::DocumentFormatNotProvided);
+			ensure!(!hash.is_empty(),Error::<T>::DocumentIPFSHashNotProvided);
+			let content = crate::cid::binary_cid(&hash).ok_or(Error::<T>::InvalidDocumentHash)?;
+			ensure!(Self::get_document_formats().contains(&format),Error::<T>::DocumentFormatNotAllowed);
+			ensure!(!DocumentsByHash::<T>::contains_key(&content),Error::<T>::DocumentAlreadySubmitted);
+			Self::note_submission(&who)?;
+
+			let uid = Self::get_total_items().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+
+			let document = Document::<T> {
+				creator: who.clone(),
+				title: title.clone(),
+				description: description.clone(),
+				format: format.clone(),
+				hash: hash.clone(),
+				status: DocumentStatus::Submitted,
+			};
+
+			Documents::<T>::insert(uid.clone(),document);
+			DocumentsByHash::<T>::insert(&content,uid);
+			DocumentsByCreator::<T>::insert(&who,uid,());
+			DocumentsByStatus::<T>::insert(DocumentStatus::Submitted,uid,());
+			let created = Self::recorded_now();
+			DocumentTimelines::<T>::insert(uid, DocumentTimeline { created: created.clone(), updated: created, verified: None });
+			TotalItems::<T>::put(&uid);
+
+			Self::deposit_event(Event::DocumentCreated { creator: who, document_id: uid });
+
+			Ok(())
+		}
+
+		/// Files a document under a category and replaces its tags. Only the creator can classify
+		/// a document, until it is verified or rejected.
+		#[pallet::weight(10_000)]
+		pub fn classify_document(origin: OriginFor<T>, document_id: u64, category: Option<u32>, tags: Vec<Vec<u8>>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let document = Self::get_document(document_id).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(document.creator == who, Error::<T>::NotAuthorized);
+			ensure!(!matches!(document.status, DocumentStatus::Verified | DocumentStatus::Rejected), Error::<T>::DocumentAlreadyFinalized);
+			if let Some(category) = category {
+				ensure!(Categories::<T>::contains_key(category), Error::<T>::CategoryNotFound);
+			}
+			ensure!(tags.len() <= MAX_TAGS_PER_DOCUMENT, Error::<T>::TooManyTags);
+			for (index, tag) in tags.iter().enumerate() {
+				ensure!(!tag.is_empty() && tag.len() <= MAX_TAG_LEN, Error::<T>::InvalidTag);
+				ensure!(!tags[..index].contains(tag), Error::<T>::DuplicateTag);
+			}
+
+			if let Some(previous) = DocumentCategories::<T>::take(document_id) {
+				DocumentsByCategory::<T>::remove((previous, document.status, document_id));
+			}
+			if let Some(category) = category {
+				DocumentCategories::<T>::insert(document_id, category);
+				DocumentsByCategory::<T>::insert((category, document.status, document_id), ());
+			}
+
+			for tag in DocumentTags::<T>::take(document_id) {
+				DocumentsByTag::<T>::remove(tag, document_id);
+			}
+			for tag in &tags {
+				DocumentsByTag::<T>::insert(tag, document_id, ());
+			}
+			DocumentTags::<T>::insert(document_id, &tags);
+			Self::touch_document(document_id, false);
+
+			Self::deposit_event(Event::DocumentClassified { document_id, category, tags });
+
+			Ok(())
+		}
+
+		/// Sets the licensing and rights metadata of a document. Only the creator can set it,
+		/// and it can no longer change once the document is verified.
+		///
+		/// Rights are kept out of `create_document` so that submitting stays a single, stable
+		/// call for existing clients, and so that a creator can settle them while the document
+		/// is under review.
+		#[pallet::weight(10_000)]
+		pub fn set_document_rights(origin: OriginFor<T>, document_id: u64, rights: RightsMetadata<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let document = Self::get_document(document_id).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(document.creator == who, Error::<T>::NotAuthorized);
+			ensure!(!matches!(document.status, DocumentStatus::Verified | DocumentStatus::Rejected), Error::<T>::RightsLocked);
+			ensure!(Self::get_licenses().contains(&rights.license), Error::<T>::LicenseNotAllowed);
+			ensure!(rights.rights_holder.len() <= MAX_RIGHTS_HOLDER_LEN, Error::<T>::RightsHolderTooLong);
+			ensure!(
+				rights.provenance.len() <= MAX_PROVENANCE_ENTRIES &&
+					rights.provenance.iter().all(|entry| !entry.is_empty() && entry.len() <= MAX_PROVENANCE_ENTRY_LEN),
+				Error::<T>::InvalidProvenance
+			);
+
+			let (license, embargo_until) = (rights.license.clone(), rights.embargo_until);
+			DocumentRights::<T>::insert(document_id, rights);
+			Self::touch_document(document_id, false);
+
+			Self::deposit_event(Event::DocumentRightsSet { document_id, license, embargo_until });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn create_qualification_voting(origin: OriginFor<T>, document_id: u64) -> DispatchResult{
+
+			let who = ensure_signed(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			Self::do_create_qualification_voting(&who, document_id, now)?;
+
+			Ok(())
+		}
+
+		/// Opens the vote for the document's next review stage after the first one.
+		#[pallet::weight(10_000)]
+		pub fn create_verification_voting(origin: OriginFor<T>, document_id: u64) -> DispatchResult{
+
+			let who = ensure_signed(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			Self::do_create_verification_voting(&who, document_id, now)?;
+
+			Ok(())
+		}
+
+		/// Creates the next vote of a document now, opening for ballots at block `start`.
+		#[pallet::weight(10_000)]
+		pub fn schedule_voting(origin: OriginFor<T>, vote_type: VoteType, document_id: u64, start: T::BlockNumber) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(start >= now, Error::<T>::VoteStartInPast);
+			ensure!(start <= now + MAX_VOTE_START_DELAY.into(), Error::<T>::VoteStartTooFar);
+
+			match vote_type {
+				VoteType::Qualification => Self::do_create_qualification_voting(&who, document_id, start)?,
+				VoteType::Verification => Self::do_create_verification_voting(&who, document_id, start)?,
+				VoteType::Application => return Err(Error::<T>::InvalidVoteType.into()),
+			};
+
+			Ok(())
+		}
+
+		/// Creates qualification votes for up to [`MAX_VOTES_PER_BATCH`] submitted documents,
+		/// opening now or at `start`. A document that cannot be put to a vote is reported in a
+		/// [`Event::QualificationVotingNotCreated`] event and does not stop the others.
+		#[pallet::weight(10_000 + Pallet::<T>::qualification_voting_weight().saturating_mul(document_ids.len() as u64))]
+		pub fn create_qualification_votings(origin: OriginFor<T>, document_ids: Vec<u64>, start: Option<T::BlockNumber>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(document_ids.len() <= MAX_VOTES_PER_BATCH, Error::<T>::TooManyDocuments);
+			let now = <frame_system::Pallet<T>>::block_number();
+			let start = start.unwrap_or(now);
+			ensure!(start >= now, Error::<T>::VoteStartInPast);
+			ensure!(start <= now + MAX_VOTE_START_DELAY.into(), Error::<T>::VoteStartTooFar);
+
+			let (mut created, mut failed) = (0u32, 0u32);
+			for document_id in document_ids {
+				let result = with_transaction(|| match Self::do_create_qualification_voting(&who, document_id, start) {
+					Ok(voting_id) => TransactionOutcome::Commit(Ok(voting_id)),
+					Err(error) => TransactionOutcome::Rollback(Err(error)),
+				});
+				match result {
+					Ok(_) => created += 1,
+					Err(error) => {
+						failed += 1;
+						Self::deposit_event(Event::QualificationVotingNotCreated { document_id, error });
+					},
+				}
+			}
+
+			Self::deposit_event(Event::QualificationVotingsCreated { created, failed });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn cast_qualification_vote(origin: OriginFor<T>, voting_id: u64, vote_cast: bool, note: Option<ReviewNote>) -> DispatchResult  {
+			let who = ensure_signed(origin)?;
+			let vote_type = VoteType::Qualification;
+			ensure!(!MemberVote::<T>::contains_key((who.clone(),vote_type.clone(),voting_id.clone())),Error::<T>::MemberAlreadyVoted);
+
+			let mut vote = Self::get_qualification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
+			Self::ensure_role(&who, vote.role)?;
+			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+			Self::ensure_no_conflict_of_interest(&who, vote.document_id)?;
+			if let Some(panel) = Self::get_review_panel(voting_id) {
+				ensure!(panel.members.contains(&who), Error::<T>::NotAPanelMember);
+			}
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= vote.start && now < vote.end, Error::<T>::VotingWindowNotValid);
+
+			if vote_cast {
+				vote.yes_votes = vote.yes_votes + 1;
+			} else {
+				vote.no_votes = vote.no_votes + 1;
+			}
+
+
+			if let Some(note) = &note {
+				Self::attach_review_note(&who, vote_type, voting_id, note)?;
+			}
+			QualificationVotes::<T>::insert(voting_id.clone(),&vote);
+			MemberVote::<T>::insert((who.clone(),vote_type.clone(),voting_id.clone()),vote_cast);
+			VoteBallots::<T>::append((vote_type, voting_id), &who);
+			Self::note_participation(&who, vote.role);
+			Self::deposit_event(Event::VoteCast { voter: who, vote_type, voting_id, aye: vote_cast, note });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn cast_verification_vote(origin: OriginFor<T>, voting_id: u64, vote_cast: bool, note: Option<ReviewNote>) -> DispatchResult  {
+			let who = ensure_signed(origin)?;
+			let vote_type = VoteType::Verification;
+			ensure!(!MemberVote::<T>::contains_key((who.clone(),vote_type.clone(),voting_id.clone())),Error::<T>::MemberAlreadyVoted);
+
+			let mut vote = Self::get_verification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
+			Self::ensure_role(&who, vote.role)?;
+			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+			Self::ensure_no_conflict_of_interest(&who, vote.document_id)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= vote.start && now < vote.end, Error::<T>::VotingWindowNotValid);
+
+			if vote_cast {
+				vote.yes_votes = vote.yes_votes + 1;
+			} else {
+				vote.no_votes = vote.no_votes + 1;
+			}
+
+			if let Some(note) = &note {
+				Self::attach_review_note(&who, vote_type, voting_id, note)?;
+			}
+			VerificationVotes::<T>::insert(voting_id.clone(),&vote);
+			MemberVote::<T>::insert((who.clone(),vote_type.clone(),voting_id.clone()),vote_cast);
+			VoteBallots::<T>::append((vote_type, voting_id), &who);
+			Self::note_participation(&who, vote.role);
+			Self::deposit_event(Event::VoteCast { voter: who, vote_type, voting_id, aye: vote_cast, note });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + Pallet::<T>::delegated_tally_weight())]
+		pub fn finalize_qualification_voting(origin: OriginFor<T>, voting_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut vote = Self::get_qualification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
+			Self::ensure_role(&who, vote.role)?;
+			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+			ensure!(Self::current_vote(vote.document_id, VoteType::Qualification) == Some(voting_id), Error::<T>::VoteNotCurrent);
+			let document = Self::get_document(vote.document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(document.status == DocumentStatus::UnderReview, Error::<T>::DocumentNotUnderReview);
+			Self::ensure_no_conflict_of_interest(&who, vote.document_id)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now > vote.end,Error::<T>::VoteStillInProgress);
+
+			Self::count_delegated_votes(VoteType::Qualification, voting_id, &document.creator, &mut vote);
+
+			let status = match Self::vote_passed(&vote) {
+				true => Self::passed_vote_status(&mut vote, &document.format),
+				false => {
+					vote.status = VoteStatus::Failed;
+					DocumentStatus::Rejected
+				},
+			};
+
+			Self::update_document_status(vote.document_id, status)?;
+			QualificationVotes::<T>::insert(voting_id.clone(),&vote);
+			Finalizations::<T>::insert((VoteType::Qualification, voting_id), Finalization { finalized: Self::recorded_now(), finalizer: who });
+			Self::queue_pruning(VoteType::Qualification, voting_id);
+			Self::deposit_event(Event::QualificationVotingEnded {
+				voting_id,
+				document_id: vote.document_id,
+				yes_votes: vote.yes_votes,
+				no_votes: vote.no_votes,
+				outcome: vote.status,
+			});
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + Pallet::<T>::delegated_tally_weight())]
+		pub fn finalize_verification_voting(origin: OriginFor<T>, voting_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut vote = Self::get_verification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
+			ensure!(Self::ensure_qualifier(who.clone()) || Self::has_role(&who, vote.role),Error::<T>::NotAuthorized);
+			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+			ensure!(Self::current_vote(vote.document_id, VoteType::Verification) == Some(voting_id), Error::<T>::VoteNotCurrent);
+			let document = Self::get_document(vote.document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(document.status == DocumentStatus::VoteInProgress, Error::<T>::DocumentNotInVerification);
+			Self::ensure_no_conflict_of_interest(&who, vote.document_id)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now > vote.end,Error::<T>::VoteStillInProgress);
+
+			Self::count_delegated_votes(VoteType::Verification, voting_id, &document.creator, &mut vote);
+
+			let status = match Self::vote_passed(&vote) {
+				true => Self::passed_vote_status(&mut vote, &document.format),
+				false => {
+					vote.status = VoteStatus::Failed;
+					DocumentStatus::Rejected
+				},
+			};
+
+			Self::update_document_status(vote.document_id, status)?;
+			VerificationVotes::<T>::insert(voting_id.clone(),&vote);
+			Finalizations::<T>::insert((VoteType::Verification, voting_id), Finalization { finalized: Self::recorded_now(), finalizer: who });
+			Self::queue_pruning(VoteType::Verification, voting_id);
+			Self::deposit_event(Event::VerificationVotingEnded {
+				voting_id,
+				document_id: vote.document_id,
+				yes_votes: vote.yes_votes,
+				no_votes: vote.no_votes,
+				outcome: vote.status,
+			});
+
+			Ok(())
+		}
+
+		/// Moves the end of an in-flight vote `extra` blocks later. Quorum and threshold stay as
+		/// snapshotted when the vote was opened.
+		#[pallet::weight(10_000)]
+		pub fn extend_vote(origin: OriginFor<T>, vote_type: VoteType, voting_id: u64, extra: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(extra > 0, Error::<T>::VotingWindowNotValid);
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			// A closed window stays closed, its tally is already known.
+			let (from, to) = match vote_type {
+				VoteType::Qualification | VoteType::Verification => {
+					let mut vote = Self::get_vote(vote_type, voting_id).ok_or(Error::<T>::VoteNotFound)?;
+					ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+					ensure!(now < vote.end, Error::<T>::VotingWindowNotValid);
+					let from = vote.end;
+					vote.end = from.checked_add(&extra.into()).ok_or(ArithmeticError::Overflow)?;
+					Self::put_vote(vote_type, voting_id, &vote);
+					(from, vote.end)
+				},
+				VoteType::Application => {
+					let mut application = Self::get_application(voting_id).ok_or(Error::<T>::ApplicationNotFound)?;
+					ensure!(application.status == VoteStatus::InProgress, Error::<T>::ApplicationNotInProgress);
+					ensure!(now < application.end, Error::<T>::VotingWindowNotValid);
+					let from = application.end;
+					application.end = from.checked_add(&extra.into()).ok_or(ArithmeticError::Overflow)?;
+					Applications::<T>::insert(voting_id, &application);
+					(from, application.end)
+				},
+			};
+
+			Self::deposit_event(Event::VoteExtended { vote_type, voting_id, from, to });
+
+			Ok(())
+		}
+
+		/// Stops an in-flight vote without an outcome. A document goes back to the status it had
+		/// before the vote was opened so that a new vote can be created, and an applicant gets its
+		/// bond back.
+		#[pallet::weight(10_000)]
+		pub fn cancel_vote(origin: OriginFor<T>, vote_type: VoteType, voting_id: u64) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match vote_type {
+				VoteType::Qualification | VoteType::Verification => {
+					let mut vote = Self::get_vote(vote_type, voting_id).ok_or(Error::<T>::VoteNotFound)?;
+					ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+
+					vote.status = VoteStatus::Cancelled;
+					Self::put_vote(vote_type, voting_id, &vote);
+					Self::queue_pruning(vote_type, voting_id);
+					ReviewPanels::<T>::remove(voting_id);
+					Self::stop_availability_checks(vote.document_id);
+					let status = match vote_type {
+						VoteType::Qualification => DocumentStatus::Submitted,
+						_ => DocumentStatus::SuccessfulReview,
+					};
+					Self::update_document_status(vote.document_id, status)?;
+				},
+				VoteType::Application => {
+					let mut application = Self::get_application(voting_id).ok_or(Error::<T>::ApplicationNotFound)?;
+					ensure!(application.status == VoteStatus::InProgress, Error::<T>::ApplicationNotInProgress);
+
+					application.status = VoteStatus::Cancelled;
+					<T as Config>::Currency::unreserve(&application.applicant, application.bond);
+					PendingApplications::<T>::remove(&application.applicant, application.role);
+					Applications::<T>::insert(voting_id, &application);
+				},
+			}
+
+			Self::deposit_event(Event::VoteCancelled { vote_type, voting_id });
+
+			Ok(())
+		}
+
+		/// Records the result of an availability check. Submitted by the offchain worker of a
+		/// qualifier without a conflict of interest. The check is conclusive once the content is
+		/// `Available` or after [`MAX_AVAILABILITY_ATTEMPTS`] failed checks; until then later
+		/// checks replace a failed one.
+		#[pallet::weight(10_000)]
+		pub fn submit_availability(origin: OriginFor<T>, document_id: u64, availability: Availability) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::ensure_qualifier(who.clone()), Error::<T>::NotAQualifier);
+			let document = Self::get_document(document_id).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(!matches!(document.status, DocumentStatus::Verified | DocumentStatus::Rejected), Error::<T>::DocumentAlreadyFinalized);
+			Self::ensure_no_conflict_of_interest(&who, document_id)?;
+			ensure!(PendingAvailability::<T>::contains_key(document_id), Error::<T>::AvailabilityNotPending);
+
+			let replaced = Self::get_availability(document_id).map(|attestation| attestation.availability);
+			let attested_at = <frame_system::Pallet<T>>::block_number();
+			Availabilities::<T>::insert(document_id, AvailabilityAttestation { availability, attester: who.clone(), attested_at });
+
+			let conclusive = match availability {
+				Availability::Available { .. } => true,
+				_ => AvailabilityAttempts::<T>::mutate(document_id, |attempts| {
+					*attempts += 1;
+					*attempts >= MAX_AVAILABILITY_ATTEMPTS
+				}),
+			};
+			if conclusive {
+				PendingAvailability::<T>::remove(document_id);
+				AvailabilityAttempts::<T>::remove(document_id);
+			}
+
+			Self::deposit_event(Event::AvailabilityAttested { document_id, attester: who, availability, replaced });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_availability_required(origin: OriginFor<T>, required: bool) -> DispatchResult {
+			ensure_root(origin)?;
+
+			AvailabilityRequired::<T>::put(required);
+
+			Self::deposit_event(Event::AvailabilityRequirementChanged { required });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			if paused && !Self::is_paused() {
+				PausedSince::<T>::put(now);
+			}
+			if !paused {
+				if let Some(since) = PausedSince::<T>::take() {
+					Self::postpone_member_deadlines(now - since);
+					Self::postpone_open_votes(since, now - since);
+				}
+			}
+			Paused::<T>::put(paused);
+
+			Self::deposit_event(Event::PauseChanged { paused });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_qualification_voting_window(origin: OriginFor<T>, window: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(window > 0, Error::<T>::VotingWindowNotValid);
+
+			QualificationVotingWindow::<T>::put(window.clone());
+
+			Self::deposit_event(Event::QualificationVotingWindowChanged { window });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_verification_voting_window(origin: OriginFor<T>, window: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(window > 0, Error::<T>::VotingWindowNotValid);
+
+			VerificationVotingWindow::<T>::put(window.clone());
+
+			Self::deposit_event(Event::VerificationVotingWindowChanged { window });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_qualification_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResult {
+			ensure_root(origin)?;
+
+			QualificationQuorum::<T>::put(quorum.clone());
+
+			Self::deposit_event(Event::QualificationQuorumChanged { quorum });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_verification_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResult {
+			ensure_root(origin)?;
+
+			VerificationQuorum::<T>::put(quorum.clone());
+
+			Self::deposit_event(Event::VerificationQuorumChanged { quorum });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn add_document_format(origin: OriginFor<T>, format: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!format.is_empty(), Error::<T>::DocumentFormatNotProvided);
+
+			let mut formats = DocumentFormats::<T>::get();
+			ensure!(!formats.contains(&format), Error::<T>::DocumentFormatAlreadyAllowed);
+			formats.push(format.clone());
+			DocumentFormats::<T>::put(formats);
+
+			Self::deposit_event(Event::DocumentFormatAdded { format });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn add_license(origin: OriginFor<T>, license: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!license.is_empty(), Error::<T>::LicenseNotAllowed);
+			ensure!(license.len() <= MAX_LICENSE_LEN, Error::<T>::LicenseTooLong);
+
+			let mut licenses = Licenses::<T>::get();
+			ensure!(!licenses.contains(&license), Error::<T>::LicenseAlreadyAllowed);
+			ensure!(licenses.len() < MAX_LICENSES, Error::<T>::TooManyLicenses);
+			licenses.push(license.clone());
+			Licenses::<T>::put(licenses);
+
+			Self::deposit_event(Event::LicenseAdded { license });
+
+			Ok(())
+		}
+
+		/// Stops accepting `license` for new rights metadata. Documents already using it keep it.
+		#[pallet::weight(10_000)]
+		pub fn remove_license(origin: OriginFor<T>, license: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let mut licenses = Licenses::<T>::get();
+			let index = licenses.iter().position(|l| *l == license).ok_or(Error::<T>::LicenseNotFound)?;
+			licenses.remove(index);
+			Licenses::<T>::put(licenses);
+
+			Self::deposit_event(Event::LicenseRemoved { license });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn add_category(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!name.is_empty() && name.len() <= MAX_CATEGORY_NAME_LEN, Error::<T>::InvalidCategoryName);
+
+			let category_id = Self::get_categories_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			Categories::<T>::insert(category_id, &name);
+			CategoriesCount::<T>::put(category_id);
+
+			Self::deposit_event(Event::CategoryAdded { category_id, name });
+
+			Ok(())
+		}
+
+		/// Removes a category no document is filed under.
+		#[pallet::weight(10_000)]
+		pub fn remove_category(origin: OriginFor<T>, category_id: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Categories::<T>::contains_key(category_id), Error::<T>::CategoryNotFound);
+			ensure!(DocumentsByCategory::<T>::iter_key_prefix((category_id,)).next().is_none(), Error::<T>::CategoryNotEmpty);
+
+			Categories::<T>::remove(category_id);
+
+			Self::deposit_event(Event::CategoryRemoved { category_id });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn remove_document_format(origin: OriginFor<T>, format: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let mut formats = DocumentFormats::<T>::get();
+			let index = formats.iter().position(|f| *f == format).ok_or(Error::<T>::DocumentFormatNotFound)?;
+			formats.remove(index);
+			DocumentFormats::<T>::put(formats);
+
+			Self::deposit_event(Event::DocumentFormatRemoved { format });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_rate_limits(origin: OriginFor<T>, limits: RateLimits) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(limits.period > 0, Error::<T>::InvalidRateLimits);
+
+			Limits::<T>::put(limits);
+
+			Self::deposit_event(Event::RateLimitsChanged { limits });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn declare_affiliation(origin: OriginFor<T>, with: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Affiliations::<T>::contains_key(&who, &with), Error::<T>::AffiliationAlreadyDeclared);
+
+			Affiliations::<T>::insert(&who, &with, ());
+
+			Self::deposit_event(Event::AffiliationDeclared { who, with });
+
+			Ok(())
+		}
+
+		/// Affiliations can only be withdrawn by governance, so members cannot drop one to vote on
+		/// a document and declare it again afterwards.
+		#[pallet::weight(10_000)]
+		pub fn remove_affiliation(origin: OriginFor<T>, who: T::AccountId, with: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Affiliations::<T>::contains_key(&who, &with), Error::<T>::AffiliationNotFound);
+
+			Affiliations::<T>::remove(&who, &with);
+
+			Self::deposit_event(Event::AffiliationRemoved { who, with });
+
+			Ok(())
+		}
+
+		/// Enables random review panels for new qualification votes, or disables them with `None`.
+		#[pallet::weight(10_000)]
+		pub fn set_review_panel_settings(origin: OriginFor<T>, settings: Option<ReviewPanelSettings>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match settings {
+				Some(settings) => {
+					ensure!(settings.size > 0, Error::<T>::InvalidReviewPanelSettings);
+					ReviewPanelConfig::<T>::put(settings);
+				},
+				None => ReviewPanelConfig::<T>::kill(),
+			}
+
+			Self::deposit_event(Event::ReviewPanelSettingsChanged { settings });
+
+			Ok(())
 		}
 
-		#[pallet::weight(10_000 )]
-		pub fn add_collector(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			ensure_root(origin.clone())?;
-			let mut collectors = Collectors::<T>::get();
+		/// Sets the review pipeline for documents of `format`. Refused while documents of the
+		/// format are under review.
+		#[pallet::weight(10_000)]
+		pub fn set_pipeline(origin: OriginFor<T>, format: Vec<u8>, stages: Vec<ReviewStage>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!format.is_empty(), Error::<T>::DocumentFormatNotProvided);
+			ensure!(!stages.is_empty() && stages.len() <= MAX_PIPELINE_STAGES, Error::<T>::InvalidPipeline);
+			ensure!(stages.iter().all(|stage| stage.window > 0), Error::<T>::VotingWindowNotValid);
+			Self::ensure_pipeline_not_in_use(&format)?;
+
+			Pipelines::<T>::insert(&format, &stages);
+
+			Self::deposit_event(Event::PipelineSet { format, stages });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn remove_pipeline(origin: OriginFor<T>, format: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Pipelines::<T>::contains_key(&format), Error::<T>::PipelineNotFound);
+			Self::ensure_pipeline_not_in_use(&format)?;
+
+			Pipelines::<T>::remove(&format);
+
+			Self::deposit_event(Event::PipelineRemoved { format });
+
+			Ok(())
+		}
+
+		/// Lets `delegate` vote on behalf of the caller in votes of `vote_type` the caller does
+		/// not vote in. Both accounts must share a role.
+		#[pallet::weight(10_000)]
+		pub fn delegate(origin: OriginFor<T>, vote_type: VoteType, delegate: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(who != delegate, Error::<T>::CannotDelegateToSelf);
+
+			let roles = [Roles::QualifierRole, Roles::CollectorRole, Roles::ContributorRole];
+			ensure!(roles.iter().any(|role| Self::has_role(&who, *role)), Error::<T>::NotAMember);
+			ensure!(
+				roles.iter().any(|role| Self::has_role(&who, *role) && Self::has_role(&delegate, *role)),
+				Error::<T>::DelegateRoleMismatch
+			);
+
+			// The chain through the caller to the new delegate and onwards must neither lead back
+			// to the caller nor exceed the depth followed at tally time.
+			let mut current = delegate.clone();
+			let mut depth = Self::incoming_delegation_depth(&who, vote_type) + 1;
+			ensure!(depth <= MAX_DELEGATION_DEPTH, Error::<T>::DelegationChainTooLong);
+			while let Some(next) = Self::get_delegation(&current, vote_type) {
+				ensure!(next != who, Error::<T>::DelegationCycle);
+				depth += 1;
+				ensure!(depth <= MAX_DELEGATION_DEPTH, Error::<T>::DelegationChainTooLong);
+				current = next;
+			}
+
+			if let Some(previous) = Self::get_delegation(&who, vote_type) {
+				Delegators::<T>::mutate(&previous, vote_type, |delegators| delegators.retain(|d| d != &who));
+			}
+			Delegations::<T>::insert(&who, vote_type, &delegate);
+			Delegators::<T>::append(&delegate, vote_type, &who);
+
+			Self::deposit_event(Event::DelegationSet { delegator: who, vote_type, delegate });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn undelegate(origin: OriginFor<T>, vote_type: VoteType) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let delegate = Self::get_delegation(&who, vote_type).ok_or(Error::<T>::DelegationNotFound)?;
+
+			Delegations::<T>::remove(&who, vote_type);
+			Delegators::<T>::mutate(&delegate, vote_type, |delegators| delegators.retain(|d| d != &who));
+
+			Self::deposit_event(Event::DelegationRevoked { delegator: who, vote_type });
+
+			Ok(())
+		}
+
+		/// Applies for `role`, reserving the application bond. `profile` is the IPFS CID of the
+		/// applicant's credentials.
+		#[pallet::weight(10_000)]
+		pub fn apply_for_role(origin: OriginFor<T>, role: Roles, profile: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::has_role(&who, role), Error::<T>::AlreadyAMember);
+			ensure!(!PendingApplications::<T>::contains_key(&who, role), Error::<T>::ApplicationAlreadyPending);
+			ensure!(crate::cid::is_valid_cid(&profile), Error::<T>::InvalidProfile);
+
+			Self::do_apply(who, role, profile)
+		}
+
+		/// Asks the other qualifiers to re-elect the caller for another term. Decided like a role
+		/// application, with `profile` the IPFS CID of the caller's current credentials; if it
+		/// fails the seat expires at the end of the current term.
+		#[pallet::weight(10_000)]
+		pub fn request_term_renewal(origin: OriginFor<T>, profile: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(QualifierTermEnds::<T>::contains_key(&who), Error::<T>::NoTermToRenew);
+			ensure!(!PendingApplications::<T>::contains_key(&who, Roles::QualifierRole), Error::<T>::ApplicationAlreadyPending);
+			ensure!(crate::cid::is_valid_cid(&profile), Error::<T>::InvalidProfile);
+
+			Self::do_apply(who, Roles::QualifierRole, profile)
+		}
+
+		/// Sets the length of qualifier terms. Seats held without a term get one starting now,
+		/// and a zero term makes every seat permanent again.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time().saturating_mul(T::MaxQualifiers::get().into()))]
+		pub fn set_qualifier_term(origin: OriginFor<T>, term: u32) -> DispatchResult {
+			ensure_root(origin)?;
+
+			QualifierTerm::<T>::put(term);
+			for who in Self::get_all_qualifiers() {
+				if term == 0 || !QualifierTermEnds::<T>::contains_key(&who) {
+					Self::start_qualifier_term(&who);
+				}
+			}
+
+			Self::deposit_event(Event::QualifierTermChanged { term });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_inactivity_policy(origin: OriginFor<T>, policy: Option<InactivityPolicy>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match policy {
+				Some(policy) => {
+					ensure!(policy.max_missed_votes > 0 && policy.grace_period > 0, Error::<T>::InvalidInactivityPolicy);
+					Inactivity::<T>::put(policy);
+				},
+				None => Inactivity::<T>::kill(),
+			}
+
+			Self::deposit_event(Event::InactivityPolicyChanged { policy });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn cast_application_vote(origin: OriginFor<T>, application_id: u64, vote_cast: bool) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vote_type = VoteType::Application;
+			ensure!(!MemberVote::<T>::contains_key((who.clone(),vote_type,application_id)),Error::<T>::MemberAlreadyVoted);
+
+			let mut application = Self::get_application(application_id).ok_or(Error::<T>::ApplicationNotFound)?;
+			Self::ensure_role(&who, application.role)?;
+			ensure!(application.status == VoteStatus::InProgress, Error::<T>::ApplicationNotInProgress);
+			ensure!(!Self::has_conflict_of_interest(&who, &application.applicant), Error::<T>::ConflictOfInterest);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= application.start && now < application.end, Error::<T>::VotingWindowNotValid);
+
+			if vote_cast {
+				application.yes_votes = application.yes_votes + 1;
+			} else {
+				application.no_votes = application.no_votes + 1;
+			}
+
+			Applications::<T>::insert(application_id, &application);
+			MemberVote::<T>::insert((who.clone(),vote_type,application_id),vote_cast);
+			Self::note_participation(&who, application.role);
+			Self::deposit_event(Event::VoteCast { voter: who, vote_type, voting_id: application_id, aye: vote_cast, note: None });
+
+			Ok(())
+		}
+
+		/// Decides an application once its window is over. Without a quorum the application
+		/// expires and the bond is returned. Otherwise a majority of `yes` ballots admits the
+		/// applicant and returns the bond, and anything else slashes it. An applicant that can no
+		/// longer be admitted, e.g. to a full role, gets the bond back and the application expires.
+		#[pallet::weight(10_000)]
+		pub fn finalize_application(origin: OriginFor<T>, application_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut application = Self::get_application(application_id).ok_or(Error::<T>::ApplicationNotFound)?;
+			ensure!(application.status == VoteStatus::InProgress, Error::<T>::ApplicationNotInProgress);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now > application.end, Error::<T>::VoteStillInProgress);
+
+			let quorum = Self::get_quorum_of_application(application_id).unwrap_or_else(ApplicationQuorum::<T>::get);
+			let quorum_met = application.yes_votes + application.no_votes >= u64::from(quorum) &&
+				Self::has_eligible_voters(&application);
+
+			match application.yes_votes > application.no_votes {
+				// Too few members could or did vote, e.g. on the renewal of a lone qualifier.
+				_ if !quorum_met => {
+					application.status = VoteStatus::Expired;
+					<T as Config>::Currency::unreserve(&application.applicant, application.bond);
+				},
+				true => {
+					application.status = VoteStatus::Passed;
+					<T as Config>::Currency::unreserve(&application.applicant, application.bond);
+					match Self::has_role(&application.applicant, application.role) {
+						true => {
+							let ends_at = Self::start_qualifier_term(&application.applicant);
+							Self::deposit_event(Event::QualifierTermRenewed { who: application.applicant.clone(), ends_at });
+						},
+						false => {
+							// The role may have filled up while the application was open.
+							if let Err(error) = Self::do_add_member(application.applicant.clone(), application.role) {
+								application.status = VoteStatus::Expired;
+								Self::deposit_event(Event::ApplicantNotAdmitted { application_id, error });
+							}
+						},
+					}
+				},
+				false => {
+					application.status = VoteStatus::Failed;
+					let _ = <T as Config>::Currency::slash_reserved(&application.applicant, application.bond);
+				},
+			}
+
+			PendingApplications::<T>::remove(&application.applicant, application.role);
+			Applications::<T>::insert(application_id, &application);
+			Finalizations::<T>::insert((VoteType::Application, application_id), Finalization { finalized: Self::recorded_now(), finalizer: who });
+			Self::deposit_event(Event::RoleApplicationDecided {
+				application_id,
+				yes_votes: application.yes_votes,
+				no_votes: application.no_votes,
+				outcome: application.status,
+			});
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn withdraw_application(origin: OriginFor<T>, application_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut application = Self::get_application(application_id).ok_or(Error::<T>::ApplicationNotFound)?;
+			ensure!(application.applicant == who, Error::<T>::NotAuthorized);
+			ensure!(application.status == VoteStatus::InProgress, Error::<T>::ApplicationNotInProgress);
+
+			application.status = VoteStatus::Expired;
+			<T as Config>::Currency::unreserve(&who, application.bond);
+			PendingApplications::<T>::remove(&who, application.role);
+			Applications::<T>::insert(application_id, &application);
+
+			Self::deposit_event(Event::RoleApplicationWithdrawn { application_id });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_application_voting_window(origin: OriginFor<T>, window: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(window > 0, Error::<T>::VotingWindowNotValid);
+
+			ApplicationVotingWindow::<T>::put(window);
+
+			Self::deposit_event(Event::ApplicationVotingWindowChanged { window });
+
+			Ok(())
+		}
+
+		/// Sets the quorum of applications opened from now on.
+		#[pallet::weight(10_000)]
+		pub fn set_application_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ApplicationQuorum::<T>::put(quorum);
+
+			Self::deposit_event(Event::ApplicationQuorumChanged { quorum });
+
+			Ok(())
+		}
+
+		/// Sets the caller's profile, reserving the profile deposit the first time.
+		#[pallet::weight(10_000)]
+		pub fn set_profile(origin: OriginFor<T>, display_name: Vec<u8>, affiliation: Vec<u8>, profile_cid: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let roles = [Roles::QualifierRole, Roles::CollectorRole, Roles::ContributorRole];
+			ensure!(roles.iter().any(|role| Self::has_role(&who, *role)), Error::<T>::NotAMember);
+			ensure!(display_name.len() <= MAX_DISPLAY_NAME_LEN, Error::<T>::DisplayNameTooLong);
+			ensure!(affiliation.len() <= MAX_AFFILIATION_LEN, Error::<T>::AffiliationTooLong);
+			ensure!(crate::cid::is_valid_cid(&profile_cid), Error::<T>::InvalidProfile);
+
+			let deposit = match Self::get_member_profile(&who) {
+				Some(profile) => profile.deposit,
+				None => {
+					let deposit = T::ProfileDeposit::get();
+					<T as Config>::Currency::reserve(&who, deposit)?;
+					deposit
+				},
+			};
+
+			MemberProfiles::<T>::insert(&who, MemberProfile { display_name, affiliation, profile_cid, deposit });
+
+			Self::deposit_event(Event::ProfileSet { who });
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn clear_profile(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let profile = MemberProfiles::<T>::take(&who).ok_or(Error::<T>::ProfileNotFound)?;
+
+			<T as Config>::Currency::unreserve(&who, profile.deposit);
+
+			Self::deposit_event(Event::ProfileCleared { who });
+
+			Ok(())
+		}
+
+	}
+
+	// Helpful functions
+	impl<T: Config> Pallet<T> {
+		pub fn ensure_contributor(who: T::AccountId) -> bool {
+
+			//let val = pallet_nft::Pallet::<T>::get_token((who.clone(),Roles::QualifierRole as u32));
+			let contributors = Contributors::<T>::get();
+
+			let check: bool;
+
+			match contributors.binary_search(&who) {
+				Ok(_) => check = true,
+				Err(_index) => check = false,
+			}
+			
+			check
+		}
+
+		pub fn ensure_collector(who: T::AccountId) -> bool {
+			let collectors = Collectors::<T>::get();
 
-			let uid = Self::collectors_uid_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			let check: bool;
 
 			match collectors.binary_search(&who) {
-				Ok(_) => Err(Error::<T>::CollectorAlreadyExists.into()),
+				Ok(_) => check = true,
+				Err(_index) => check = false,
+			}
+			
+			check
+		}
+
+		pub fn ensure_qualifier(who: T::AccountId) -> bool {
+			let qualifiers = Qualifiers::<T>::get();
+
+			let check: bool;
+
+			match qualifiers.binary_search(&who) {
+				Ok(_) => check = true,
+				Err(_index) => check = false,
+			}
+			
+			check
+		}
+
+		/// The review pipeline for documents of `format`.
+		pub fn pipeline_for(format: &[u8]) -> Vec<ReviewStage> {
+			Pipelines::<T>::get(format).unwrap_or_else(Self::default_pipeline)
+		}
+
+		/// Fails while documents of `format` are between their first and last stage, whose
+		/// position in the pipeline a new one could not honour.
+		fn ensure_pipeline_not_in_use(format: &[u8]) -> DispatchResult {
+			let in_flight = [DocumentStatus::UnderReview, DocumentStatus::SuccessfulReview, DocumentStatus::VoteInProgress]
+				.into_iter()
+				.flat_map(DocumentsByStatus::<T>::iter_key_prefix)
+				.any(|document_id| Self::get_document(document_id).map_or(false, |document| document.format == format));
+			ensure!(!in_flight, Error::<T>::PipelineInUse);
+
+			Ok(())
+		}
+
+		/// Qualification by qualifiers followed by verification by contributors, using the
+		/// governance-set windows and quorums.
+		pub fn default_pipeline() -> Vec<ReviewStage> {
+			sp_std::vec![
+				ReviewStage {
+					role: Roles::QualifierRole,
+					window: QualificationVotingWindow::<T>::get(),
+					quorum: QualificationQuorum::<T>::get(),
+					threshold: Percent::from_percent(50),
+				},
+				ReviewStage {
+					role: Roles::ContributorRole,
+					window: VerificationVotingWindow::<T>::get(),
+					quorum: VerificationQuorum::<T>::get(),
+					threshold: Percent::from_percent(50),
+				},
+			]
+		}
+
+		/// Whether a vote reached its quorum and its `yes` share exceeds its threshold.
+		pub fn vote_passed(vote: &Vote<T>) -> bool {
+			let total_votes = vote.yes_votes + vote.no_votes;
+			let yes = u128::from(vote.yes_votes) * 100;
+			let required = u128::from(vote.threshold.deconstruct()) * u128::from(total_votes);
+
+			total_votes >= vote.quorum.into() && yes > required
+		}
+
+		/// Settles a vote that passed, returning the document's new status. A document that would
+		/// be verified without a required `Available` attestation is rejected and the vote fails,
+		/// so that finalizing never gets stuck on an unavailable document.
+		fn passed_vote_status(vote: &mut Vote<T>, format: &[u8]) -> DocumentStatus {
+			let status = Self::advance_stage(vote.document_id, format, vote.stage);
+			if status == DocumentStatus::Verified && !Self::meets_availability_requirement(vote.document_id) {
+				vote.status = VoteStatus::Failed;
+				Self::deposit_event(Event::DocumentRejectedAsUnavailable { document_id: vote.document_id });
+				return DocumentStatus::Rejected
+			}
+
+			vote.status = VoteStatus::Passed;
+			status
+		}
+
+		/// Moves a document past `stage` after its vote passed, returning its new status.
+		fn advance_stage(document_id: u64, format: &[u8], stage: u32) -> DocumentStatus {
+			let next = stage.saturating_add(1);
+			match (next as usize) < Self::pipeline_for(format).len() {
+				true => {
+					DocumentStages::<T>::insert(document_id, next);
+					DocumentStatus::SuccessfulReview
+				},
+				false => DocumentStatus::Verified,
+			}
+		}
+
+		/// Adds `who` to `role`, assigns the next role uid and mints the role NFT.
+		pub fn do_add_member(who: T::AccountId, role: Roles) -> DispatchResult {
+			let mut members = Self::members(role);
+			let max_members = match role {
+				Roles::QualifierRole => T::MaxQualifiers::get(),
+				Roles::CollectorRole => T::MaxCollectors::get(),
+				Roles::ContributorRole => T::MaxContributors::get(),
+			};
+
+			let uid = match role {
+				Roles::QualifierRole => Self::qualifiers_uid_count(),
+				Roles::CollectorRole => Self::collectors_uid_count(),
+				Roles::ContributorRole => Self::contributors_uid_count(),
+			}.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+
+			match members.binary_search(&who) {
+				Ok(_) => Err(match role {
+					Roles::QualifierRole => Error::<T>::QualifierAlreadyExists,
+					Roles::CollectorRole => Error::<T>::CollectorAlreadyExists,
+					Roles::ContributorRole => Error::<T>::ContributorAlreadyExists,
+				}.into()),
 				Err(index) => {
-					collectors.insert(index, who.clone());
-					Collectors::<T>::put(collectors);
-					CollectorsCount::<T>::put(uid.clone());
+					ensure!(members.len() < max_members as usize, Error::<T>::TooManyMembers);
+					members.insert(index, who.clone());
+					match role {
+						Roles::QualifierRole => {
+							Qualifiers::<T>::put(members);
+							QualifiersCount::<T>::put(uid);
+						},
+						Roles::CollectorRole => {
+							Collectors::<T>::put(members);
+							CollectorsCount::<T>::put(uid);
+						},
+						Roles::ContributorRole => {
+							Contributors::<T>::put(members);
+							ContributorsCount::<T>::put(uid);
+						},
+					}
+					let joined_at = <frame_system::Pallet<T>>::block_number();
+					Memberships::<T>::insert(&who, role, Membership { uid, joined_at });
+					if role == Roles::QualifierRole {
+						Self::start_qualifier_term(&who);
+					}
 					//mint NFT
-					pallet_nft::Pallet::<T>::mint(origin.clone(),Roles::CollectorRole as u32,who.clone()).ok();
-					Self::deposit_event(Event::CollectorAdded(who,uid));
+					pallet_nft::Pallet::<T>::mint(frame_system::RawOrigin::Root.into(),role as u32,who.clone()).ok();
+					Self::deposit_event(match role {
+						Roles::QualifierRole => Event::QualifierAdded { who, uid },
+						Roles::CollectorRole => Event::CollectorAdded { who, uid },
+						Roles::ContributorRole => Event::ContributorAdded { who, uid },
+					});
 					Ok(())
 				}
 			}
+		}
+
+		/// Opens an application of `who` for `role`, reserving the application bond.
+		fn do_apply(who: T::AccountId, role: Roles, profile: Vec<u8>) -> DispatchResult {
+			let bond = T::ApplicationBond::get();
+			<T as Config>::Currency::reserve(&who, bond)?;
+
+			let uid = Self::get_application_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			let end = now + ApplicationVotingWindow::<T>::get().into();
+
+			let application = Application::<T> {
+				applicant: who.clone(),
+				role,
+				profile,
+				bond,
+				yes_votes: 0,
+				no_votes: 0,
+				start: now,
+				end,
+				status: VoteStatus::InProgress,
+			};
+
+			Applications::<T>::insert(uid, &application);
+			ApplicationsCount::<T>::put(uid);
+			PendingApplications::<T>::insert(&who, role, uid);
+			ApplicationQuorums::<T>::insert(uid, ApplicationQuorum::<T>::get());
+
+			Self::deposit_event(Event::RoleApplicationSubmitted { application_id: uid, applicant: who, role, end });
+
+			Ok(())
+		}
+
+		/// Whether any member of the role applied for may vote on the application.
+		fn has_eligible_voters(application: &Application<T>) -> bool {
+			Self::members(application.role)
+				.iter()
+				.any(|member| !Self::has_conflict_of_interest(member, &application.applicant))
+		}
+
+		/// Removes `who` from `role` and burns its role NFT.
+		pub fn do_remove_member(who: &T::AccountId, role: Roles) -> DispatchResult {
+			let mut members = Self::members(role);
+			let index = members.binary_search(who).map_err(|_| Error::<T>::NotAMember)?;
+			members.remove(index);
+
+			match role {
+				Roles::QualifierRole => {
+					Qualifiers::<T>::put(members);
+					QualifierTermEnds::<T>::remove(who);
+				},
+				Roles::CollectorRole => Collectors::<T>::put(members),
+				Roles::ContributorRole => Contributors::<T>::put(members),
+			}
+			Memberships::<T>::remove(who, role);
+			Participations::<T>::remove(who, role);
+			pallet_nft::Pallet::<T>::do_burn(who.clone(), role as u32).ok();
+
+			Self::deposit_event(Event::MemberRemoved { who: who.clone(), role });
+
+			Ok(())
+		}
+
+		/// Starts a new term for `who` from the later of now and its current term end, returning
+		/// when it ends. Seats are permanent while the term length is zero.
+		fn start_qualifier_term(who: &T::AccountId) -> Option<T::BlockNumber> {
+			let term = QualifierTerm::<T>::get();
+			if term == 0 {
+				QualifierTermEnds::<T>::remove(who);
+				return None
+			}
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let start = Self::get_qualifier_term_end(who).map_or(now, |end| end.max(now));
+			let end = start + term.into();
+			QualifierTermEnds::<T>::insert(who, end);
+			TermExpiries::<T>::append(end, who);
+
+			Some(end)
+		}
+
+		/// Moves every pending term end and suspension `by` blocks later, so that the blocks the
+		/// DAO was paused do not count against its members.
+		fn postpone_member_deadlines(by: T::BlockNumber) {
+			if by.is_zero() {
+				return
+			}
+
+			let expiries: Vec<_> = TermExpiries::<T>::drain().collect();
+			for (at, accounts) in expiries {
+				for who in accounts {
+					// Entries of superseded terms are dropped.
+					if Self::get_qualifier_term_end(&who) == Some(at) {
+						QualifierTermEnds::<T>::insert(&who, at + by);
+						TermExpiries::<T>::append(at + by, who);
+					}
+				}
+			}
+
+			let suspensions: Vec<_> = SuspensionsDue::<T>::drain().collect();
+			for (at, members) in suspensions {
+				for (who, role) in members {
+					let mut participation = Self::get_participation(&who, role);
+					if participation.suspend_at == Some(at) {
+						participation.suspend_at = Some(at + by);
+						Participations::<T>::insert(&who, role, participation);
+						SuspensionsDue::<T>::append(at + by, (who, role));
+					}
+				}
+			}
+		}
+
+		/// Moves the windows of open document votes and role applications `by` blocks later, so
+		/// that the ballots members could not cast during a pause from `since` can still be cast.
+		/// Windows that had not opened by `since` move as a whole.
+		fn postpone_open_votes(since: T::BlockNumber, by: T::BlockNumber) {
+			if by.is_zero() {
+				return
+			}
+
+			let open = [(DocumentStatus::UnderReview, VoteType::Qualification), (DocumentStatus::VoteInProgress, VoteType::Verification)];
+			for (status, vote_type) in open {
+				let documents: Vec<u64> = DocumentsByStatus::<T>::iter_key_prefix(status).collect();
+				for document_id in documents {
+					let voting_id = match Self::current_vote(document_id, vote_type) {
+						Some(voting_id) => voting_id,
+						None => continue,
+					};
+					if let Some(mut vote) = Self::get_vote(vote_type, voting_id) {
+						if vote.status == VoteStatus::InProgress {
+							if vote.start >= since {
+								vote.start = vote.start.saturating_add(by);
+							}
+							vote.end = vote.end.saturating_add(by);
+							Self::put_vote(vote_type, voting_id, &vote);
+						}
+					}
+				}
+			}
 
+			for (_, _, application_id) in PendingApplications::<T>::iter() {
+				Applications::<T>::mutate(application_id, |application| {
+					if let Some(application) = application {
+						if application.start >= since {
+							application.start = application.start.saturating_add(by);
+						}
+						application.end = application.end.saturating_add(by);
+					}
+				});
+			}
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn add_contributor(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			ensure_root(origin.clone())?;
-			let mut contributors = Contributors::<T>::get();
-
-			let uid = Self::contributors_uid_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
-
-			match contributors.binary_search(&who) {
-				Ok(_) => Err(Error::<T>::ContributorAlreadyExists.into()),
-				Err(index) => {
-					contributors.insert(index, who.clone());
-					Contributors::<T>::put(contributors);
-					ContributorsCount::<T>::put(uid.clone());
-					//mint NFT
-					pallet_nft::Pallet::<T>::mint(origin.clone(),Roles::ContributorRole as u32,who.clone()).ok();
-					Self::deposit_event(Event::ContributorAdded(who,uid));
-					Ok(())
+		/// Removes qualifiers whose term ends at `now`, returning the weight used.
+		fn expire_qualifier_terms(now: T::BlockNumber) -> Weight {
+			let expiring = TermExpiries::<T>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+			for who in expiring {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if Self::get_qualifier_term_end(&who) == Some(now) {
+					Self::do_remove_member(&who, Roles::QualifierRole).ok();
+					Self::deposit_event(Event::QualifierTermExpired { who });
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(4, 6));
 				}
 			}
 
+			weight
 		}
 
+		/// Checks the content of documents waiting for an attestation against this node's IPFS
+		/// gateway and submits the results, a few documents per block.
+		fn attest_pending_availability(now: T::BlockNumber) {
+			let signer = Signer::<T, T::AppCrypto>::any_account();
+			if !signer.can_sign() {
+				return
+			}
+			let gateway = crate::ipfs::gateway();
+
+			let pending = PendingAvailability::<T>::iter()
+				// Documents entered in this block are checked from the next one, and each is
+				// retried every few blocks until a conclusive attestation lands.
+				.filter(|(_, since)| now > *since && (now - *since) % AVAILABILITY_RETRY_BLOCKS.into() == 1u32.into())
+				.take(MAX_AVAILABILITY_CHECKS_PER_BLOCK);
+
+			for (document_id, _) in pending {
+				let document = match Self::get_document(document_id) {
+					Some(document) => document,
+					None => continue,
+				};
+
+				// A failed request says nothing about the content, the next retry checks again.
+				let availability = match crate::ipfs::check(&gateway, &document.hash, &document.format, T::MaxDocumentSize::get()) {
+					Ok(availability) => availability,
+					Err(error) => {
+						log::warn!(target: "runtime::bhdao", "IPFS gateway request for document {} failed: {:?}", document_id, error);
+						continue
+					},
+				};
+
+				let result = signer.send_signed_transaction(|_| Call::submit_availability { document_id, availability });
+				if let Some((_, Err(()))) = result {
+					log::warn!(target: "runtime::bhdao", "Failed to submit availability of document {}", document_id);
+				}
+			}
+		}
 
-		#[pallet::weight(10_000)]
-		pub fn create_document(origin: OriginFor<T>, title: Vec<u8>, description: Vec<u8>,
-		format: Vec<u8>, hash: Vec<u8>) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_contributor(who.clone()),Error::<T>::NotAContributor);
-			ensure!(!title.is_empty(),Error::<T>::DocumentTitleNotProvided);
-			ensure!(!description.is_empty(),Error::<T>::DocumentDescriptionNotProvided);
-			ensure!(!format.is_empty(),Error::<T>::DocumentFormatNotProvided);
-			ensure!(!hash.is_empty(),Error::<T>::DocumentIPFSHashNotProvided);
-
-			let uid = Self::get_total_items().checked_add(1).ok_or(ArithmeticError::Overflow)?;
-
-			let document = Document::<T> {
-				creator: who.clone(),
-				title: title.clone(),
-				description: description.clone(),
-				format: format.clone(),
-				hash: hash.clone(),
-				status: DocumentStatus::Submitted,
-			};
-
-			Documents::<T>::insert(uid.clone(),document);
-			TotalItems::<T>::put(&uid);
-
-			Self::deposit_event(Event::DocumentCreated(who,uid));
+		/// Fails while verification needs an `Available` attestation the document does not have.
+		fn ensure_available(document_id: u64) -> DispatchResult {
+			ensure!(Self::meets_availability_requirement(document_id), Error::<T>::DocumentNotAvailable);
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn create_qualification_voting(origin: OriginFor<T>, document_id: u64) -> DispatchResult{
-
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_qualifier(who.clone()),Error::<T>::NotAQualifier);
+		/// Whether `document_id` may be verified: either availability is not required, or the
+		/// document has an `Available` attestation.
+		fn meets_availability_requirement(document_id: u64) -> bool {
+			!Self::is_availability_required() ||
+				Self::get_availability(document_id)
+					.map_or(false, |attestation| matches!(attestation.availability, Availability::Available { .. }))
+		}
 
-			let mut document = Self::get_document(document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+		/// Creates the qualification vote of a document, opening for ballots at `start`.
+		fn do_create_qualification_voting(who: &T::AccountId, document_id: u64, start: T::BlockNumber) -> Result<u64, DispatchError> {
+			let document = Self::get_document(document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+			let stage = Self::pipeline_for(&document.format).first().copied().ok_or(Error::<T>::ReviewStageNotFound)?;
+			Self::ensure_role(who, stage.role)?;
+			Self::ensure_no_conflict_of_interest(who, document_id)?;
 
-			ensure!(document.status == DocumentStatus::Submitted, Error::<T>::VerificationVoteAlreadyCreated);
+			Self::ensure_status_transition(document.status, DocumentStatus::UnderReview)?;
+			Self::note_vote_created(who)?;
 
 			let uid = Self::get_qualification_vote_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
 
-			let now = <frame_system::Pallet<T>>::block_number();
-
-			let end = now + QualificationVotingWindow::<T>::get().into();
+			let end = start + stage.window.into();
 
-			let vote = Vote::<T> {
+			let mut vote = Vote::<T> {
 				document_id: document_id,
 				yes_votes: 0,
 				no_votes: 0,
-				start: now,
+				start: start,
 				end: end,
 				status: VoteStatus::InProgress,
+				stage: 0,
+				role: stage.role,
+				quorum: stage.quorum,
+				threshold: stage.threshold,
 			};
 
+			if let Some(settings) = Self::get_review_panel_settings() {
+				let panel = Self::draw_review_panel(uid, &document.creator, stage.role, settings)?;
+				vote.quorum = panel.quorum;
+				ReviewPanels::<T>::insert(uid, &panel);
+				Self::deposit_event(Event::ReviewPanelDrawn { voting_id: uid, members: panel.members, quorum: panel.quorum });
+			}
+
 			QualificationVotes::<T>::insert(uid.clone(),&vote);
 			QualificationVotesCount::<T>::put(uid.clone());
-			Self::deposit_event(Event::QualificationVotingStarted(uid));
-
-			document.status = DocumentStatus::UnderReview;
-			Documents::<T>::insert(document_id.clone(),document);
-			Self::deposit_event(Event::DocumentStatusUpdated(document_id,1));
-			
-			Ok(())
-		}
+			DocumentVotes::<T>::append(document_id, VoteType::Qualification, uid);
+			DocumentStages::<T>::insert(document_id, 0);
+			Self::start_availability_checks(document_id);
+			Self::deposit_event(Event::QualificationVotingStarted { voting_id: uid, document_id, start, end });
 
-		#[pallet::weight(10_000)]
-		pub fn create_verification_voting(origin: OriginFor<T>, document_id: u64) -> DispatchResult{
+			Self::update_document_status(document_id, DocumentStatus::UnderReview)?;
 
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_qualifier(who.clone()) || Self::ensure_contributor(who.clone()),Error::<T>::NotAuthorized);
+			Ok(uid)
+		}
 
-			let mut document = Self::get_document(document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+		/// Creates the vote of a document's current review stage after the first one, opening for
+		/// ballots at `start`.
+		fn do_create_verification_voting(who: &T::AccountId, document_id: u64, start: T::BlockNumber) -> Result<u64, DispatchError> {
+			let document = Self::get_document(document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
+			let index = Self::get_document_stage(document_id);
+			let stage = Self::pipeline_for(&document.format).get(index as usize).copied().ok_or(Error::<T>::ReviewStageNotFound)?;
+			ensure!(Self::ensure_qualifier(who.clone()) || Self::has_role(who, stage.role),Error::<T>::NotAuthorized);
+			Self::ensure_no_conflict_of_interest(who, document_id)?;
 
-			ensure!(document.status == DocumentStatus::SuccessfulReview, Error::<T>::DocumentNotReviewed);
+			Self::ensure_status_transition(document.status, DocumentStatus::VoteInProgress)?;
+			Self::note_vote_created(who)?;
 
 			let uid = Self::get_verification_vote_count().checked_add(1).ok_or(ArithmeticError::Overflow)?;
 
-			let now = <frame_system::Pallet<T>>::block_number();
-
-			let end = now + VerificationVotingWindow::<T>::get().into();
+			let end = start + stage.window.into();
 
 			let vote = Vote::<T> {
 				document_id: document_id,
 				yes_votes: 0,
 				no_votes: 0,
-				start: now,
+				start: start,
 				end: end,
 				status: VoteStatus::InProgress,
+				stage: index,
+				role: stage.role,
+				quorum: stage.quorum,
+				threshold: stage.threshold,
 			};
 
 			VerificationVotes::<T>::insert(uid.clone(),&vote);
 			VerificationVotesCount::<T>::put(uid.clone());
-			Self::deposit_event(Event::VerificationVotingStarted(uid));
+			DocumentVotes::<T>::append(document_id, VoteType::Verification, uid);
+			Self::start_availability_checks(document_id);
+			Self::deposit_event(Event::VerificationVotingStarted { voting_id: uid, document_id, start, end });
 
-			document.status = DocumentStatus::VoteInProgress;
-			Documents::<T>::insert(document_id.clone(),document);
-			Self::deposit_event(Event::DocumentStatusUpdated(document_id,3));
-			
-			Ok(())
+			Self::update_document_status(document_id, DocumentStatus::VoteInProgress)?;
+
+			Ok(uid)
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn cast_qualification_vote(origin: OriginFor<T>, voting_id: u64, vote_cast: bool) -> DispatchResult  {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_qualifier(who.clone()),Error::<T>::NotAQualifier);
-			let vote_type = VoteType::Qualification;
-			ensure!(!MemberVote::<T>::contains_key((who.clone(),vote_type.clone(),voting_id.clone())),Error::<T>::MemberAlreadyVoted);
+		/// Has the offchain workers check the content of a document put to a vote, unless it was
+		/// already found available or is being checked.
+		fn start_availability_checks(document_id: u64) {
+			let available = Self::get_availability(document_id)
+				.map_or(false, |attestation| matches!(attestation.availability, Availability::Available { .. }));
+			if !available && !PendingAvailability::<T>::contains_key(document_id) {
+				PendingAvailability::<T>::insert(document_id, <frame_system::Pallet<T>>::block_number());
+			}
+		}
 
-			let mut vote = Self::get_qualification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
-			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
-			let now = <frame_system::Pallet<T>>::block_number();
-			ensure!(now > vote.start && now < vote.end, Error::<T>::VotingWindowNotValid);
+		/// Stops checking the content of a document that no longer has an open vote.
+		fn stop_availability_checks(document_id: u64) {
+			PendingAvailability::<T>::remove(document_id);
+			AvailabilityAttempts::<T>::remove(document_id);
+		}
 
-			if vote_cast {
-				vote.yes_votes = vote.yes_votes + 1;
-			} else {
-				vote.no_votes = vote.no_votes + 1;
+		/// Schedules the records of a finished document vote for pruning after the retention period.
+		pub(crate) fn queue_pruning(vote_type: VoteType, voting_id: u64) {
+			let due = <frame_system::Pallet<T>>::block_number() + T::VoteRetentionPeriod::get();
+			PruneQueueBounds::<T>::mutate(|(_, tail)| {
+				PruneQueue::<T>::insert(*tail, (due, vote_type, voting_id));
+				*tail += 1;
+			});
+		}
+
+		/// Prunes the finished document votes that are due at `now`, oldest first, within
+		/// `remaining_weight`. A vote with more ballots than fit in one block is cleared over
+		/// several blocks; its summary is written once all its ballots are gone.
+		fn prune_finished_votes(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			let ballot_weight = db.writes(3);
+			let vote_weight = db.reads_writes(3, 8);
+			let mut used = db.reads(1);
+			if used.saturating_add(vote_weight) > remaining_weight {
+				return used
 			}
 
+			let (mut head, tail) = PruneQueueBounds::<T>::get();
+			while head < tail && used.saturating_add(vote_weight) <= remaining_weight {
+				used = used.saturating_add(db.reads(1));
+				let (due, vote_type, voting_id) = match PruneQueue::<T>::get(head) {
+					Some(entry) => entry,
+					None => {
+						head += 1;
+						continue
+					},
+				};
+				if due > now {
+					break
+				}
+
+				let mut voters = VoteBallots::<T>::get((vote_type, voting_id));
+				while let Some(voter) = voters.last() {
+					if used.saturating_add(vote_weight).saturating_add(ballot_weight) > remaining_weight {
+						break
+					}
+					MemberVote::<T>::remove((voter.clone(), vote_type, voting_id));
+					ReviewNotes::<T>::remove((voter.clone(), vote_type, voting_id));
+					voters.pop();
+					used = used.saturating_add(ballot_weight);
+				}
+				if !voters.is_empty() {
+					VoteBallots::<T>::insert((vote_type, voting_id), voters);
+					used = used.saturating_add(db.writes(1));
+					break
+				}
 
-			QualificationVotes::<T>::insert(voting_id.clone(),&vote);
-			MemberVote::<T>::insert((who.clone(),vote_type.clone(),voting_id.clone()),vote_cast);
-			Self::deposit_event(Event::VoteCast(0,voting_id));
+				if let Some(vote) = Self::get_vote(vote_type, voting_id) {
+					VoteSummaries::<T>::append(vote.document_id, VoteSummary {
+						vote_type,
+						voting_id,
+						stage: vote.stage,
+						yes_votes: vote.yes_votes,
+						no_votes: vote.no_votes,
+						outcome: vote.status,
+					});
+					match vote_type {
+						VoteType::Qualification => {
+							QualificationVotes::<T>::remove(voting_id);
+							ReviewPanels::<T>::remove(voting_id);
+						},
+						_ => VerificationVotes::<T>::remove(voting_id),
+					}
+					Self::deposit_event(Event::VotePruned { vote_type, voting_id, document_id: vote.document_id });
+				}
+				VoteBallots::<T>::remove((vote_type, voting_id));
+				ReviewNotesCount::<T>::remove((vote_type, voting_id));
+				PruneQueue::<T>::remove(head);
+				head += 1;
+				used = used.saturating_add(vote_weight);
+			}
 
-			Ok(())
+			PruneQueueBounds::<T>::put((head, tail));
+			used.saturating_add(db.writes(1))
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn cast_verification_vote(origin: OriginFor<T>, voting_id: u64, vote_cast: bool) -> DispatchResult  {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_contributor(who.clone()),Error::<T>::NotAContributor);
-			let vote_type = VoteType::Verification;
-			ensure!(!MemberVote::<T>::contains_key((who.clone(),vote_type.clone(),voting_id.clone())),Error::<T>::MemberAlreadyVoted);
+		fn recorded_now() -> RecordedAt<T> {
+			RecordedAt { block: <frame_system::Pallet<T>>::block_number(), time: T::Time::now() }
+		}
 
-			let mut vote = Self::get_verification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
-			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
+		/// Marks a document as updated now, and as verified if `verified`.
+		fn touch_document(document_id: u64, verified: bool) {
+			DocumentTimelines::<T>::mutate(document_id, |timeline| {
+				if let Some(timeline) = timeline {
+					timeline.updated = Self::recorded_now();
+					if verified {
+						timeline.verified = Some(timeline.updated.clone());
+					}
+				}
+			});
+		}
+
+		/// Whether the content of a document is still under embargo.
+		pub fn is_embargoed(document_id: u64) -> bool {
 			let now = <frame_system::Pallet<T>>::block_number();
-			ensure!(now > vote.start && now < vote.end, Error::<T>::VotingWindowNotValid);
+			Self::get_document_rights(document_id)
+				.and_then(|rights| rights.embargo_until)
+				.map_or(false, |until| now < until)
+		}
 
-			if vote_cast {
-				vote.yes_votes = vote.yes_votes + 1;
-			} else {
-				vote.no_votes = vote.no_votes + 1;
+		/// Documents filed under `category`, optionally only those in `status`. Like the other
+		/// index queries it returns at most `limit` documents, capped at
+		/// [`MAX_DOCUMENTS_PER_PAGE`], following `start_after`, the last document of the
+		/// previous page. Without a status, a document that changes status between two pages
+		/// may be skipped or listed twice.
+		pub fn documents_in_category(category: u32, status: Option<DocumentStatus>, start_after: Option<u64>, limit: u32) -> Vec<u64> {
+			let limit = Self::page_size(limit);
+			match status {
+				Some(status) => {
+					let documents = match start_after {
+						Some(document_id) => DocumentsByCategory::<T>::iter_key_prefix_from(
+							(category, status),
+							DocumentsByCategory::<T>::hashed_key_for((category, status, document_id)),
+						),
+						None => DocumentsByCategory::<T>::iter_key_prefix((category, status)),
+					};
+					documents.take(limit).collect()
+				},
+				None => {
+					let start = start_after.and_then(|document_id| {
+						let status = Self::get_document(document_id)?.status;
+						Some(DocumentsByCategory::<T>::hashed_key_for((category, status, document_id)))
+					});
+					let documents = match start {
+						Some(start) => DocumentsByCategory::<T>::iter_key_prefix_from((category,), start),
+						None => DocumentsByCategory::<T>::iter_key_prefix((category,)),
+					};
+					documents.take(limit).map(|(_, document_id)| document_id).collect()
+				},
 			}
-
-			VerificationVotes::<T>::insert(voting_id.clone(),&vote);
-			MemberVote::<T>::insert((who.clone(),vote_type.clone(),voting_id.clone()),vote_cast);
-			Self::deposit_event(Event::VoteCast(1,voting_id));
-
-			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn finalize_qualification_voting(origin: OriginFor<T>, voting_id: u64) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_qualifier(who.clone()),Error::<T>::NotAQualifier);
+		pub fn documents_with_tag(tag: &[u8], start_after: Option<u64>, limit: u32) -> Vec<u64> {
+			let tag = tag.to_vec();
+			let documents = match start_after {
+				Some(document_id) => DocumentsByTag::<T>::iter_key_prefix_from(&tag, DocumentsByTag::<T>::hashed_key_for(&tag, document_id)),
+				None => DocumentsByTag::<T>::iter_key_prefix(&tag),
+			};
+			documents.take(Self::page_size(limit)).collect()
+		}
 
-			let mut vote = Self::get_qualification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
-			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
-			let mut document = Self::get_document(vote.document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
-			ensure!(document.status == DocumentStatus::UnderReview, Error::<T>::DocumentNotUnderReview);
-			let now = <frame_system::Pallet<T>>::block_number();
-			ensure!(now > vote.end,Error::<T>::VoteStillInProgress);
+		pub fn documents_by_creator(creator: &T::AccountId, start_after: Option<u64>, limit: u32) -> Vec<u64> {
+			let documents = match start_after {
+				Some(document_id) => DocumentsByCreator::<T>::iter_key_prefix_from(creator, DocumentsByCreator::<T>::hashed_key_for(creator, document_id)),
+				None => DocumentsByCreator::<T>::iter_key_prefix(creator),
+			};
+			documents.take(Self::page_size(limit)).collect()
+		}
 
-			let quorum = QualificationQuorum::<T>::get().into();
-			let total_votes = vote.yes_votes + vote.no_votes;
+		pub fn documents_in_status(status: DocumentStatus, start_after: Option<u64>, limit: u32) -> Vec<u64> {
+			let documents = match start_after {
+				Some(document_id) => DocumentsByStatus::<T>::iter_key_prefix_from(status, DocumentsByStatus::<T>::hashed_key_for(status, document_id)),
+				None => DocumentsByStatus::<T>::iter_key_prefix(status),
+			};
+			documents.take(Self::page_size(limit)).collect()
+		}
 
-			if total_votes < quorum {
-				vote.status = VoteStatus::Failed;
-				document.status = DocumentStatus::Rejected;
-				QualificationVotes::<T>::insert(voting_id.clone(),&vote);
-				Documents::<T>::insert(vote.document_id.clone(),document);
-				Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,5));
-				Self::deposit_event(Event::QualificationVotingEnded(voting_id));
+		fn page_size(limit: u32) -> usize {
+			limit.min(MAX_DOCUMENTS_PER_PAGE) as usize
+		}
 
-				return Ok(());
+		/// Stores the review note `who` attached to its ballot in a vote.
+		fn attach_review_note(who: &T::AccountId, vote_type: VoteType, voting_id: u64, note: &ReviewNote) -> DispatchResult {
+			match note {
+				ReviewNote::Text(text) => ensure!(text.len() <= MAX_REVIEW_NOTE_LEN, Error::<T>::ReviewNoteTooLong),
+				ReviewNote::Cid(cid) => ensure!(
+					cid.len() <= crate::cid::MAX_CID_LEN && crate::cid::is_valid_cid(cid),
+					Error::<T>::InvalidReviewNote
+				),
 			}
 
-			match vote.yes_votes > vote.no_votes {
-				true => {
-					vote.status = VoteStatus::Passed;
-					document.status = DocumentStatus::SuccessfulReview;
-					QualificationVotes::<T>::insert(voting_id.clone(),&vote);
-					Documents::<T>::insert(vote.document_id.clone(),document);
-					Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,2));
-					Self::deposit_event(Event::QualificationVotingEnded(voting_id));
-				},
-				false => {
-					vote.status = VoteStatus::Failed;
-					document.status = DocumentStatus::Rejected;
-					QualificationVotes::<T>::insert(voting_id.clone(),&vote);
-					Documents::<T>::insert(vote.document_id.clone(),document);
-					Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,5));
-					Self::deposit_event(Event::QualificationVotingEnded(voting_id));
-				},
-			}
+			ReviewNotesCount::<T>::try_mutate((vote_type, voting_id), |count| -> DispatchResult {
+				ensure!(*count < T::MaxReviewNotesPerVote::get(), Error::<T>::TooManyReviewNotes);
+				*count += 1;
+				Ok(())
+			})?;
+			ReviewNotes::<T>::insert((who.clone(), vote_type, voting_id), note);
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn finalize_verification_voting(origin: OriginFor<T>, voting_id: u64) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::ensure_qualifier(who.clone()) || Self::ensure_contributor(who.clone()),Error::<T>::NotAuthorized);
+		/// Ballots of a document vote that carry a review note, with the note, in the order they
+		/// were cast.
+		pub fn review_notes(vote_type: VoteType, voting_id: u64) -> Vec<(T::AccountId, bool, ReviewNote)> {
+			VoteBallots::<T>::get((vote_type, voting_id))
+				.into_iter()
+				.filter_map(|voter| {
+					let note = Self::get_review_note((voter.clone(), vote_type, voting_id))?;
+					let aye = Self::get_member_vote((voter.clone(), vote_type, voting_id))?;
+					Some((voter, aye, note))
+				})
+				.collect()
+		}
 
-			let mut vote = Self::get_verification_vote(voting_id.clone()).ok_or(Error::<T>::VoteNotFound)?;
-			ensure!(vote.status == VoteStatus::InProgress, Error::<T>::VoteNotInProgress);
-			let mut document = Self::get_document(vote.document_id.clone()).ok_or(Error::<T>::DocumentNotFound)?;
-			ensure!(document.status == DocumentStatus::VoteInProgress, Error::<T>::IncorrectDocumentStatus);
+		/// Records that `who` voted as `role`, lifting an inactivity flag.
+		fn note_participation(who: &T::AccountId, role: Roles) {
 			let now = <frame_system::Pallet<T>>::block_number();
-			ensure!(now > vote.end,Error::<T>::VoteStillInProgress);
-
-			let quorum = VerificationQuorum::<T>::get().into();
-			let total_votes = vote.yes_votes + vote.no_votes;
-
-			if total_votes < quorum {
-				vote.status = VoteStatus::Failed;
-				document.status = DocumentStatus::Rejected;
-				VerificationVotes::<T>::insert(voting_id.clone(),&vote);
-				Documents::<T>::insert(vote.document_id.clone(),document);
-				Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,5));
-				Self::deposit_event(Event::VerificationVotingEnded(voting_id));
-
-				return Ok(());
+			let participation = Participations::<T>::mutate(who, role, |participation| {
+				participation.last_active = now;
+				participation.missed_votes = 0;
+				participation.suspend_at.take()
+			});
+
+			if participation.is_some() {
+				Self::deposit_event(Event::MemberReactivated { who: who.clone(), role });
 			}
+		}
 
-			match vote.yes_votes > vote.no_votes {
-				true => {
-					vote.status = VoteStatus::Passed;
-					document.status = DocumentStatus::Verified;
-					VerificationVotes::<T>::insert(voting_id.clone(),&vote);
-					Documents::<T>::insert(vote.document_id.clone(),document);
-					Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,4));
-					Self::deposit_event(Event::VerificationVotingEnded(voting_id));
-				},
-				false => {
-					vote.status = VoteStatus::Failed;
-					document.status = DocumentStatus::Rejected;
-					VerificationVotes::<T>::insert(voting_id.clone(),&vote);
-					Documents::<T>::insert(vote.document_id.clone(),document);
-					Self::deposit_event(Event::DocumentStatusUpdated(vote.document_id,5));
-					Self::deposit_event(Event::VerificationVotingEnded(voting_id));
+		/// Records that `who` missed a vote as `role`, flagging it once it crosses the inactivity
+		/// policy threshold.
+		fn note_missed_vote(who: &T::AccountId, role: Roles) {
+			let mut participation = Self::get_participation(who, role);
+			participation.missed_votes = participation.missed_votes.saturating_add(1);
+
+			match Self::get_inactivity_policy() {
+				Some(policy) if participation.suspend_at.is_none() && participation.missed_votes >= policy.max_missed_votes => {
+					let suspend_at = <frame_system::Pallet<T>>::block_number() + policy.grace_period.into();
+					participation.suspend_at = Some(suspend_at);
+					SuspensionsDue::<T>::append(suspend_at, (who.clone(), role));
+					Self::deposit_event(Event::MemberFlaggedInactive {
+						who: who.clone(),
+						role,
+						missed_votes: participation.missed_votes,
+						suspend_at,
+					});
 				},
+				_ => (),
 			}
+			Participations::<T>::insert(who, role, participation);
+		}
 
-			Ok(())
+		/// Removes members whose grace period ends at `now` and who have not voted since being
+		/// flagged, or lifts their flag if no policy is set anymore. Returns the weight used.
+		fn suspend_inactive_members(now: T::BlockNumber) -> Weight {
+			let due = SuspensionsDue::<T>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(2, 1);
+			let enforced = Self::get_inactivity_policy().is_some();
+
+			for (who, role) in due {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if Self::get_participation(&who, role).suspend_at != Some(now) {
+					continue
+				}
+				if enforced {
+					Self::do_remove_member(&who, role).ok();
+					Self::deposit_event(Event::MemberSuspended { who, role });
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(4, 6));
+				} else {
+					// Without a policy the flag is lifted, so that the member can be flagged
+					// again once a policy is set.
+					Participations::<T>::mutate(&who, role, |participation| participation.suspend_at = None);
+					weight = weight.saturating_add(T::DbWeight::get().writes(1));
+				}
+			}
+
+			weight
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn set_qualification_voting_window(origin: OriginFor<T>, window: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			ensure!(window > 0, Error::<T>::VotingWindowNotValid);
+		/// Roles held by `who`, with when each was joined.
+		pub fn memberships(who: &T::AccountId) -> Vec<(Roles, Membership<T>)> {
+			Memberships::<T>::iter_prefix(who).collect()
+		}
 
-			QualificationVotingWindow::<T>::put(window.clone());
+		/// Members of `role`, sorted.
+		pub fn members(role: Roles) -> Vec<T::AccountId> {
+			match role {
+				Roles::QualifierRole => Self::get_all_qualifiers(),
+				Roles::CollectorRole => Self::get_all_collectors(),
+				Roles::ContributorRole => Self::get_all_contributors(),
+			}
+		}
 
-			Self::deposit_event(Event::QualificationVotingWindowChanged(window));
+		pub fn has_role(who: &T::AccountId, role: Roles) -> bool {
+			Self::members(role).binary_search(who).is_ok()
+		}
 
-			Ok(())
+		/// Fails with the role specific error if `who` is not a member of `role`.
+		pub fn ensure_role(who: &T::AccountId, role: Roles) -> DispatchResult {
+			match role {
+				_ if Self::has_role(who, role) => Ok(()),
+				Roles::QualifierRole => Err(Error::<T>::NotAQualifier.into()),
+				Roles::CollectorRole => Err(Error::<T>::NotACollector.into()),
+				Roles::ContributorRole => Err(Error::<T>::NotAContributor.into()),
+			}
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn set_verification_voting_window(origin: OriginFor<T>, window: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			ensure!(window > 0, Error::<T>::VotingWindowNotValid);
+		/// Adds a ballot to `vote` for every eligible member who did not vote but whose delegation
+		/// chain ends at a member who did, and notes a missed vote for the remaining ones.
+		fn count_delegated_votes(vote_type: VoteType, voting_id: u64, creator: &T::AccountId, vote: &mut Vote<T>) {
+			let panel = match vote_type {
+				VoteType::Qualification => Self::get_review_panel(voting_id),
+				_ => None,
+			};
+			let (mut yes_votes, mut no_votes) = (0u64, 0u64);
+
+			for member in Self::members(vote.role) {
+				if MemberVote::<T>::contains_key((member.clone(), vote_type, voting_id)) ||
+					Self::has_conflict_of_interest(&member, creator) ||
+					panel.as_ref().map_or(false, |panel| !panel.members.contains(&member))
+				{
+					continue
+				}
 
-			VerificationVotingWindow::<T>::put(window.clone());
+				match Self::resolve_delegated_ballot(&member, vote_type, voting_id) {
+					Some(aye) => {
+						match aye {
+							true => yes_votes += 1,
+							false => no_votes += 1,
+						}
+						Self::note_participation(&member, vote.role);
+					},
+					None => Self::note_missed_vote(&member, vote.role),
+				}
+			}
 
-			Self::deposit_event(Event::VerificationVotingWindowChanged(window));
+			if yes_votes + no_votes > 0 {
+				vote.yes_votes = vote.yes_votes.saturating_add(yes_votes);
+				vote.no_votes = vote.no_votes.saturating_add(no_votes);
+				Self::deposit_event(Event::DelegatedVotesCounted { vote_type, voting_id, yes_votes, no_votes });
+			}
+		}
 
-			Ok(())
+		/// Worst-case weight of `count_delegated_votes`: every member of the largest role checks its
+		/// ballot, conflicts and a full delegation chain, and updates its participation.
+		pub fn delegated_tally_weight() -> u64 {
+			T::DbWeight::get()
+				.reads_writes(4 + 2 * MAX_DELEGATION_DEPTH as u64, 1)
+				.ref_time()
+				.saturating_mul(Self::max_role_size().into())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn set_qualification_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResult {
-			ensure_root(origin)?;
+		/// Worst-case weight of opening one qualification vote: checking the document and the
+		/// caller, drawing a review panel from the largest role, and writing the vote, its panel,
+		/// the document status and their indexes.
+		pub fn qualification_voting_weight() -> u64 {
+			T::DbWeight::get()
+				.reads_writes(10 + 2 * Self::max_role_size() as u64, 8)
+				.ref_time()
+		}
 
-			QualificationQuorum::<T>::put(quorum.clone());
+		fn max_role_size() -> u32 {
+			T::MaxQualifiers::get().max(T::MaxCollectors::get()).max(T::MaxContributors::get())
+		}
+
+		/// Length of the longest delegation chain ending at `who`, up to `MAX_DELEGATION_DEPTH`.
+		fn incoming_delegation_depth(who: &T::AccountId, vote_type: VoteType) -> u32 {
+			let mut depth = 0;
+			let mut delegators = Self::get_delegators(who, vote_type);
+			while !delegators.is_empty() && depth < MAX_DELEGATION_DEPTH {
+				depth += 1;
+				delegators = delegators.iter().flat_map(|d| Self::get_delegators(d, vote_type)).collect();
+			}
+			depth
+		}
+
+		/// Follows the delegation chain of `who` to the first delegate who voted.
+		pub fn resolve_delegated_ballot(who: &T::AccountId, vote_type: VoteType, voting_id: u64) -> Option<bool> {
+			let mut current = who.clone();
+			for _ in 0..MAX_DELEGATION_DEPTH {
+				current = Self::get_delegation(&current, vote_type)?;
+				if let Some(aye) = Self::get_member_vote((current.clone(), vote_type, voting_id)) {
+					return Some(aye)
+				}
+			}
+			None
+		}
 
-			Self::deposit_event(Event::QualificationQuorumChanged(quorum));
+		/// Fails if `who` created the document or has an affiliation with its creator.
+		pub fn ensure_no_conflict_of_interest(who: &T::AccountId, document_id: u64) -> DispatchResult {
+			let document = Self::get_document(document_id).ok_or(Error::<T>::DocumentNotFound)?;
+			ensure!(!Self::has_conflict_of_interest(who, &document.creator), Error::<T>::ConflictOfInterest);
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn set_verification_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResult {
-			ensure_root(origin)?;
+		fn has_conflict_of_interest(who: &T::AccountId, creator: &T::AccountId) -> bool {
+			who == creator ||
+				Affiliations::<T>::contains_key(who, creator) ||
+				Affiliations::<T>::contains_key(creator, who)
+		}
 
-			VerificationQuorum::<T>::put(quorum.clone());
+		/// Draws up to `settings.size` distinct members of `role` without a conflict of interest with
+		/// `creator`, seeded from the runtime randomness and the voting id.
+		fn draw_review_panel(voting_id: u64, creator: &T::AccountId, role: Roles, settings: ReviewPanelSettings) -> Result<ReviewPanel<T>, DispatchError> {
+			let mut eligible: Vec<T::AccountId> = Self::members(role)
+				.into_iter()
+				.filter(|q| !Self::has_conflict_of_interest(q, creator))
+				.collect();
+			ensure!(!eligible.is_empty(), Error::<T>::NoEligibleReviewers);
+
+			// Randomness is unavailable during the first epochs of a chain; the draw then only
+			// depends on the voting id.
+			let (seed, _) = T::Randomness::random(&(b"bhdao/panel", voting_id).encode());
+			let seed = seed.unwrap_or_default();
+
+			// Partial Fisher-Yates shuffle: the first `size` entries become the panel.
+			let size = (settings.size as usize).min(eligible.len());
+			for i in 0..size {
+				let hash = sp_io::hashing::blake2_256(&(seed, i as u32).encode());
+				let random = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]) as usize;
+				let j = i + random % (eligible.len() - i);
+				eligible.swap(i, j);
+			}
+			eligible.truncate(size);
 
-			Self::deposit_event(Event::VerificationQuorumChanged(quorum));
+			let quorum = settings.quorum.mul_ceil(size as u32);
+			Ok(ReviewPanel { members: eligible, quorum })
+		}
 
-			Ok(())
+		/// Counts a document submission by `who` against the rate limits.
+		fn note_submission(who: &T::AccountId) -> DispatchResult {
+			let limits = Self::get_rate_limits();
+			let mut activity = Self::current_activity(who, &limits);
+
+			ensure!(activity.open_submissions < limits.max_open_submissions, Error::<T>::TooManyOpenSubmissions);
+			ensure!(activity.submissions_in_period < limits.max_submissions_per_period, Error::<T>::SubmissionRateLimitExceeded);
+			activity.open_submissions += 1;
+			activity.submissions_in_period += 1;
+
+			AccountActivities::<T>::insert(who, activity);
+			Self::note_transaction(who)
 		}
 
-	}
+		/// Counts a vote opened by `who` against the rate limits.
+		fn note_vote_created(who: &T::AccountId) -> DispatchResult {
+			let limits = Self::get_rate_limits();
+			let mut activity = Self::current_activity(who, &limits);
 
-	// Helpful functions
-	impl<T: Config> Pallet<T> {
-		pub fn ensure_contributor(who: T::AccountId) -> bool {
+			ensure!(activity.votes_created_in_period < limits.max_votes_created_per_period, Error::<T>::VoteCreationRateLimitExceeded);
+			activity.votes_created_in_period += 1;
 
-			//let val = pallet_nft::Pallet::<T>::get_token((who.clone(),Roles::QualifierRole as u32));
-			let contributors = Contributors::<T>::get();
+			AccountActivities::<T>::insert(who, activity);
+			Self::note_transaction(who)
+		}
 
-			let check: bool;
+		/// The activity of `who`, with the period counters reset if its period has elapsed.
+		fn current_activity(who: &T::AccountId, limits: &RateLimits) -> AccountActivity<T::BlockNumber> {
+			let mut activity = Self::get_account_activity(who);
+			let now = <frame_system::Pallet<T>>::block_number();
 
-			match contributors.binary_search(&who) {
-				Ok(_) => check = true,
-				Err(_index) => check = false,
+			if now >= activity.period_start + limits.period.into() {
+				activity.period_start = now;
+				activity.submissions_in_period = 0;
+				activity.votes_created_in_period = 0;
 			}
-			
-			check
+
+			activity
 		}
 
-		pub fn ensure_collector(who: T::AccountId) -> bool {
-			let collectors = Collectors::<T>::get();
+		fn note_transaction(who: &T::AccountId) -> DispatchResult {
+			let total = Self::get_total_transactions().checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			let count = Self::get_transactions_per_address(who).unwrap_or(0).checked_add(1).ok_or(ArithmeticError::Overflow)?;
 
-			let check: bool;
+			TotalTransactions::<T>::put(total);
+			TransactionsPerAddress::<T>::insert(who, count);
 
-			match collectors.binary_search(&who) {
-				Ok(_) => check = true,
-				Err(_index) => check = false,
+			Ok(())
+		}
+
+		/// The most recent vote of `vote_type` opened for `document_id`, if any.
+		pub fn current_vote(document_id: u64, vote_type: VoteType) -> Option<u64> {
+			DocumentVotes::<T>::get(document_id, vote_type).last().copied()
+		}
+
+		/// A qualification or verification vote record.
+		fn get_vote(vote_type: VoteType, voting_id: u64) -> Option<Vote<T>> {
+			match vote_type {
+				VoteType::Qualification => Self::get_qualification_vote(voting_id),
+				VoteType::Verification => Self::get_verification_vote(voting_id),
+				VoteType::Application => None,
 			}
-			
-			check
 		}
 
-		pub fn ensure_qualifier(who: T::AccountId) -> bool {
-			let qualifiers = Qualifiers::<T>::get();
+		fn put_vote(vote_type: VoteType, voting_id: u64, vote: &Vote<T>) {
+			match vote_type {
+				VoteType::Qualification => QualificationVotes::<T>::insert(voting_id, vote),
+				VoteType::Verification => VerificationVotes::<T>::insert(voting_id, vote),
+				VoteType::Application => (),
+			}
+		}
 
-			let check: bool;
+		/// The document's current qualification vote record, if one was ever opened.
+		pub fn document_qualification_vote(document_id: u64) -> Option<(u64, Vote<T>)> {
+			let voting_id = Self::current_vote(document_id, VoteType::Qualification)?;
+			Self::get_qualification_vote(voting_id).map(|vote| (voting_id, vote))
+		}
 
-			match qualifiers.binary_search(&who) {
-				Ok(_) => check = true,
-				Err(_index) => check = false,
+		/// The document's current verification vote record, if one was ever opened.
+		pub fn document_verification_vote(document_id: u64) -> Option<(u64, Vote<T>)> {
+			let voting_id = Self::current_vote(document_id, VoteType::Verification)?;
+			Self::get_verification_vote(voting_id).map(|vote| (voting_id, vote))
+		}
+
+		/// Checks `from -> to` against the document lifecycle:
+		///
+		/// `Submitted -> UnderReview -> SuccessfulReview -> VoteInProgress -> Verified`, where
+		/// `UnderReview` and `VoteInProgress` may also end in `Rejected`. Pipelines with more
+		/// stages go back from `VoteInProgress` to `SuccessfulReview`, and single-stage pipelines go
+		/// straight from `UnderReview` to `Verified`. `Verified` and `Rejected` are final. Cancelling
+		/// a vote moves the document back from `UnderReview` to `Submitted` or from `VoteInProgress`
+		/// to `SuccessfulReview`.
+		pub fn ensure_status_transition(from: DocumentStatus, to: DocumentStatus) -> DispatchResult {
+			use DocumentStatus::*;
+
+			match (from, to) {
+				(Submitted, UnderReview) |
+				(UnderReview, Submitted) |
+				(UnderReview, SuccessfulReview) |
+				(UnderReview, Verified) |
+				(UnderReview, Rejected) |
+				(SuccessfulReview, VoteInProgress) |
+				(VoteInProgress, SuccessfulReview) |
+				(VoteInProgress, Verified) |
+				(VoteInProgress, Rejected) => Ok(()),
+				(Verified, _) | (Rejected, _) => Err(Error::<T>::DocumentAlreadyFinalized.into()),
+				(UnderReview, UnderReview) => Err(Error::<T>::QualificationVoteAlreadyCreated.into()),
+				(VoteInProgress, VoteInProgress) => Err(Error::<T>::VerificationVoteAlreadyCreated.into()),
+				(_, UnderReview) => Err(Error::<T>::DocumentNotSubmitted.into()),
+				(_, SuccessfulReview) => Err(Error::<T>::DocumentNotUnderReview.into()),
+				(_, VoteInProgress) => Err(Error::<T>::DocumentNotReviewed.into()),
+				(_, Verified) => Err(Error::<T>::DocumentNotInVerification.into()),
+				(_, Rejected) => Err(Error::<T>::DocumentNotUnderVote.into()),
+				(_, Submitted) => Err(Error::<T>::InvalidDocumentStatusTransition.into()),
 			}
-			
-			check
 		}
 
-		pub fn update_document_status(document_uid: u64, status: u8) -> DispatchResult {
+		pub fn update_document_status(document_uid: u64, status: DocumentStatus) -> DispatchResult {
 			let mut document = Self::get_document(document_uid).ok_or(Error::<T>::DocumentNotFound)?;
 
-			match status {
-				0 => {
-					document.status = DocumentStatus::Submitted;
-				},
-				1 => {
-					document.status = DocumentStatus::UnderReview;
-				},
-				2 => {
-					document.status = DocumentStatus::SuccessfulReview;
-				},
-				3 => {
-					document.status = DocumentStatus::VoteInProgress;
-				},
-				4 => {
-					document.status = DocumentStatus::Verified;
-				},
-				5 => {
-					document.status = DocumentStatus::Rejected;
-				},
-				_ => ()
+			let from = document.status;
+			Self::ensure_status_transition(from, status)?;
+			if status == DocumentStatus::Verified {
+				Self::ensure_available(document_uid)?;
+			}
+			document.status = status;
+
+			if matches!(status, DocumentStatus::Verified | DocumentStatus::Rejected) {
+				Self::stop_availability_checks(document_uid);
+				AccountActivities::<T>::mutate(&document.creator, |activity| {
+					activity.open_submissions = activity.open_submissions.saturating_sub(1);
+				});
 			}
 
 			Documents::<T>::insert(&document_uid, &document);
-			Self::deposit_event(Event::DocumentStatusUpdated(document_uid,status));
+			DocumentsByStatus::<T>::remove(from, document_uid);
+			DocumentsByStatus::<T>::insert(status, document_uid, ());
+			if let Some(category) = Self::get_document_category(document_uid) {
+				DocumentsByCategory::<T>::remove((category, from, document_uid));
+				DocumentsByCategory::<T>::insert((category, status, document_uid), ());
+			}
+			Self::touch_document(document_uid, status == DocumentStatus::Verified);
+			Self::deposit_event(Event::DocumentStatusUpdated { document_id: document_uid, from, to: status });
 
 			Ok(())
 		}
 		
 	}
-}
+}
\ No newline at end of file
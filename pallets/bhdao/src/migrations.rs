@@ -0,0 +1,198 @@
+//! Storage migrations for pallet_bhdao.
+
+pub mod v1 {
+	use crate::{
+		Config, DocumentStages, DocumentStatus, Documents, Pallet, QualificationVotes, ReviewStage,
+		VerificationVotes, Vote, VoteStatus,
+	};
+	use codec::Decode;
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use sp_std::marker::PhantomData;
+
+	/// Layout of [`Vote`] before review pipelines.
+	#[derive(Decode)]
+	struct OldVote<BlockNumber> {
+		document_id: u64,
+		yes_votes: u64,
+		no_votes: u64,
+		start: BlockNumber,
+		end: BlockNumber,
+		status: VoteStatus,
+	}
+
+	fn upgrade<T: Config>(old: OldVote<T::BlockNumber>, stage: u32, config: ReviewStage) -> Vote<T> {
+		Vote {
+			document_id: old.document_id,
+			yes_votes: old.yes_votes,
+			no_votes: old.no_votes,
+			start: old.start,
+			end: old.end,
+			status: old.status,
+			stage,
+			role: config.role,
+			quorum: config.quorum,
+			threshold: config.threshold,
+		}
+	}
+
+	/// Moves existing votes and documents onto the default two-stage pipeline: qualification
+	/// votes become stage 0 and verification votes stage 1, taking the current windows, quorums
+	/// and a simple majority threshold.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let pipeline = Pallet::<T>::default_pipeline();
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			QualificationVotes::<T>::translate::<OldVote<T::BlockNumber>, _>(|_, old| {
+				reads += 1;
+				writes += 1;
+				Some(upgrade::<T>(old, 0, pipeline[0]))
+			});
+			VerificationVotes::<T>::translate::<OldVote<T::BlockNumber>, _>(|_, old| {
+				reads += 1;
+				writes += 1;
+				Some(upgrade::<T>(old, 1, pipeline[1]))
+			});
+
+			// Documents past qualification are in the verification stage.
+			for (document_id, document) in Documents::<T>::iter() {
+				reads += 1;
+				if matches!(
+					document.status,
+					DocumentStatus::SuccessfulReview |
+						DocumentStatus::VoteInProgress |
+						DocumentStatus::Verified
+				) {
+					DocumentStages::<T>::insert(document_id, 1);
+					writes += 1;
+				}
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+	}
+}
+
+pub mod v2 {
+	use crate::{Config, Documents, DocumentsByCreator, DocumentsByStatus, Pallet};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use sp_std::marker::PhantomData;
+
+	/// Builds the creator and status indexes of existing documents. Documents start without a
+	/// category or tags.
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			for (document_id, document) in Documents::<T>::iter() {
+				reads += 1;
+				writes += 2;
+				DocumentsByCreator::<T>::insert(&document.creator, document_id, ());
+				DocumentsByStatus::<T>::insert(document.status, document_id, ());
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+	}
+}
+
+pub mod v3 {
+	use crate::{
+		Config, DocumentStatus, DocumentTimeline, DocumentTimelines, Documents, DocumentsByHash,
+		MemberVote, Membership, Memberships, Pallet, QualificationVotes, RecordedAt, Roles,
+		VerificationVotes, VoteBallots, VoteStatus, VoteType,
+	};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use sp_runtime::traits::Zero;
+	use sp_std::marker::PhantomData;
+
+	/// Backfills what this version started recording for existing state: the duplicate content
+	/// index, document timelines, memberships, and the voters and pruning schedule of votes that
+	/// already finished.
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			for (document_id, document) in Documents::<T>::iter() {
+				reads += 2;
+				// Hashes stored before CIDs were validated are kept as they are.
+				let content = crate::cid::binary_cid(&document.hash).unwrap_or(document.hash);
+				// Documents are visited in storage order; the first submission of some content,
+				// the one with the lowest id, keeps it.
+				if DocumentsByHash::<T>::get(&content).map_or(true, |existing| document_id < existing) {
+					DocumentsByHash::<T>::insert(&content, document_id);
+					writes += 1;
+				}
+				// When these documents were created or verified is unknown; block 0 stands in.
+				if !DocumentTimelines::<T>::contains_key(document_id) {
+					let unknown = RecordedAt::<T> { block: Zero::zero(), time: Zero::zero() };
+					let verified = (document.status == DocumentStatus::Verified).then(|| unknown.clone());
+					DocumentTimelines::<T>::insert(document_id, DocumentTimeline { created: unknown.clone(), updated: unknown, verified });
+					writes += 1;
+				}
+			}
+
+			for role in [Roles::QualifierRole, Roles::CollectorRole, Roles::ContributorRole] {
+				reads += 1;
+				for member in Pallet::<T>::members(role) {
+					reads += 1;
+					if Memberships::<T>::contains_key(&member, role) {
+						continue
+					}
+					// The member's badge carries the uid it was given; when it joined is unknown.
+					reads += 1;
+					let uid = pallet_nft::Pallet::<T>::get_token((member.clone(), role as u32)).map_or(0, |token| token.id);
+					Memberships::<T>::insert(&member, role, Membership { uid, joined_at: Zero::zero() });
+					writes += 1;
+				}
+			}
+
+			for (voter, vote_type, voting_id) in MemberVote::<T>::iter_keys() {
+				reads += 1;
+				if matches!(vote_type, VoteType::Qualification | VoteType::Verification) {
+					VoteBallots::<T>::append((vote_type, voting_id), &voter);
+					writes += 1;
+				}
+			}
+			let finished = QualificationVotes::<T>::iter()
+				.map(|(voting_id, vote)| (VoteType::Qualification, voting_id, vote.status))
+				.chain(VerificationVotes::<T>::iter().map(|(voting_id, vote)| (VoteType::Verification, voting_id, vote.status)));
+			for (vote_type, voting_id, status) in finished {
+				reads += 1;
+				if status != VoteStatus::InProgress {
+					Pallet::<T>::queue_pruning(vote_type, voting_id);
+					writes += 2;
+				}
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+	}
+}
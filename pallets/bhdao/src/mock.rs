@@ -1,16 +1,18 @@
 use crate as pallet_bhdao;
-use frame_support::traits::{ConstU16, ConstU32, ConstU64, ConstU128,OnFinalize, OnInitialize};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, ConstU128, Contains, OnFinalize, OnInitialize, Randomness, Time};
 use frame_system as system;
+use frame_system::offchain::{AppCrypto, CreateSignedTransaction, SendTransactionTypes, SigningTypes};
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, Hash, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
 pub type Balance = u128;
+pub type Extrinsic = TestXt<Call, ()>;
 
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -26,8 +28,20 @@ frame_support::construct_runtime!(
 	}
 );
 
+/// Same pause rule as the runtime's call filter.
+pub struct PauseFilter;
+impl Contains<Call> for PauseFilter {
+	fn contains(call: &Call) -> bool {
+		match call {
+			_ if !Bhdao::is_paused() => true,
+			Call::Bhdao(_) | Call::Nft(_) => false,
+			_ => true,
+		}
+	}
+}
+
 impl system::Config for Test {
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = PauseFilter;
 	type BlockWeights = ();
 	type BlockLength = ();
 	type DbWeight = ();
@@ -56,10 +70,38 @@ impl system::Config for Test {
 impl pallet_bhdao::Config for Test {
 	type Event = Event;
 	type Currency = Balances;
+	type ApplicationBond = ConstU128<1_000>;
+	type ProfileDeposit = ConstU128<100>;
+	type MaxReviewNotesPerVote = ConstU32<2>;
+	type AppCrypto = TestAppCrypto;
+	type VoteRetentionPeriod = ConstU64<10>;
+	type Time = MockTime;
+	type MaxDocumentSize = ConstU64<1_000_000>;
+	type MaxQualifiers = ConstU32<200>;
+	type MaxCollectors = ConstU32<100>;
+	type MaxContributors = ConstU32<1000>;
+	type Randomness = TestRandomness;
+}
+
+/// Deterministic randomness derived from the subject and the current block.
+pub struct TestRandomness;
+
+impl Randomness<Option<H256>, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (Option<H256>, u64) {
+		let block_number = System::block_number();
+		(Some(BlakeTwo256::hash_of(&(subject, block_number))), block_number)
+	}
 }
 
 impl pallet_nft::Config for Test {
 	type Event = Event;
+	type Currency = Balances;
+	type MetadataDepositBase = ConstU128<10>;
+	type AttributeDepositBase = ConstU128<5>;
+	type DepositPerByte = ConstU128<1>;
+	type StringLimit = ConstU32<64>;
+	type KeyLimit = ConstU32<16>;
+	type ValueLimit = ConstU32<32>;
 }
 
 impl pallet_balances::Config for Test {
@@ -76,6 +118,51 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
+/// Six second blocks starting at the Unix epoch.
+pub struct MockTime;
+impl Time for MockTime {
+	type Moment = u64;
+
+	fn now() -> u64 {
+		System::block_number() * 6_000
+	}
+}
+
+pub struct TestAppCrypto;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAppCrypto {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<C> SendTransactionTypes<C> for Test
+where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<C> CreateSignedTransaction<C> for Test
+where
+	Call: From<C>,
+{
+	fn create_transaction<A: AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
 pub fn run_to_block(n: u64) {
 	while System::block_number() < n {
 		Bhdao::on_finalize(System::block_number());
@@ -86,5 +173,9 @@ pub fn run_to_block(n: u64) {
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: (1..=20).map(|who| (who, 10_000)).collect() }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	t.into()
 }
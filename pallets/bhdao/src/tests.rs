@@ -1,5 +1,9 @@
-use crate::{mock::*, Error};
+use crate::{mock::*, Availability, DocumentStatus, Error, InactivityPolicy, RateLimits, ReviewNote, ReviewPanelSettings, ReviewStage, RightsMetadata, Roles, VoteStatus, VoteSummary, VoteType};
 use frame_support::{assert_noop, assert_ok};
+use sp_runtime::Percent;
+
+const CID_1: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+const CID_2: &[u8] = b"QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
 
 #[test]
 fn it_adds_qualifier_should_work() {
@@ -59,7 +63,7 @@ fn it_adds_collector_should_fail() {
 fn it_creates_document_should_work() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
-		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"pdf".to_vec(),b"https://ipfs.hash".to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
 		assert_eq!(Bhdao::get_total_items(),1);
 	});
 	
@@ -87,7 +91,7 @@ fn it_changes_verification_voting_window_should_work() {
 fn it_creates_qualification_voting_should_work() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
-		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"pdf".to_vec(),b"https://ipfs.hash".to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
 		assert_eq!(Bhdao::get_total_items(),1);
 		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
 		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
@@ -116,7 +120,7 @@ fn it_changes_verification_quorum_should_work() {
 fn it_creates_and_finalizes_qualification_voting_should_work() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
-		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"pdf".to_vec(),b"https://ipfs.hash".to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
 		assert_eq!(Bhdao::get_total_items(),1);
 		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
 		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
@@ -127,10 +131,11 @@ fn it_creates_and_finalizes_qualification_voting_should_work() {
 fn it_casts_votes_and_verifies_voting_should_work() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Bhdao::init_collections(Origin::root()));
-		//Create Three contributors
+		//Create Four contributors
 		assert_ok!(Bhdao::add_contributor(Origin::root(),1));
 		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
 		assert_ok!(Bhdao::add_contributor(Origin::root(),3));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),7));
 		// Create three qualifiers
 		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
 		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
@@ -138,7 +143,7 @@ fn it_casts_votes_and_verifies_voting_should_work() {
 
 		// create a new document
 
-		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"pdf".to_vec(),b"https://ipfs.hash".to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
 		assert_eq!(Bhdao::get_total_items(),1);
 
 		run_to_block(10);
@@ -154,9 +159,9 @@ fn it_casts_votes_and_verifies_voting_should_work() {
 		run_to_block(20);
 
 		//cast votes two Yays One nay
-		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true));
-		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true));
-		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(6),1,false));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(6),1,false,None));
 
 		// Skip 100 blocks
 		run_to_block(120);
@@ -170,19 +175,1430 @@ fn it_casts_votes_and_verifies_voting_should_work() {
 		run_to_block(150);
 
 		// Create Verification voting for Document 1
-		assert_ok!(Bhdao::create_verification_voting(Origin::signed(2),1));
+		assert_noop!(Bhdao::create_verification_voting(Origin::signed(2),1), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::create_verification_voting(Origin::signed(1),1));
 
 		run_to_block(155);
 
 		//cast votes two Yays One nay
-		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(1),1,true));
-		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(2),1,true));
-		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(3),1,false));
+		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(1),1,true,None));
+		// The document creator cannot vote on it
+		assert_noop!(Bhdao::cast_verification_vote(Origin::signed(2),1,true,None), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(7),1,true,None));
+		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(3),1,false,None));
 
 		// Skip 100 blocks
 		run_to_block(255);
 
 		// Finalize verification voting
-		assert_ok!(Bhdao::finalize_verification_voting(Origin::signed(2),1));
+		assert_noop!(Bhdao::finalize_verification_voting(Origin::signed(2),1), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::finalize_verification_voting(Origin::signed(1),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Verified);
 	});	
-}
\ No newline at end of file
+}
+
+const ALL_STATUSES: [DocumentStatus; 6] = [
+	DocumentStatus::Submitted,
+	DocumentStatus::UnderReview,
+	DocumentStatus::SuccessfulReview,
+	DocumentStatus::VoteInProgress,
+	DocumentStatus::Verified,
+	DocumentStatus::Rejected,
+];
+
+#[test]
+fn it_allows_only_legal_status_transitions() {
+	use DocumentStatus::*;
+	let legal = [
+		(Submitted, UnderReview),
+		(UnderReview, Submitted),
+		(UnderReview, SuccessfulReview),
+		(UnderReview, Verified),
+		(UnderReview, Rejected),
+		(SuccessfulReview, VoteInProgress),
+		(VoteInProgress, SuccessfulReview),
+		(VoteInProgress, Verified),
+		(VoteInProgress, Rejected),
+	];
+
+	for from in ALL_STATUSES {
+		for to in ALL_STATUSES {
+			let result = Bhdao::ensure_status_transition(from, to);
+			assert_eq!(result.is_ok(), legal.contains(&(from, to)), "{:?} -> {:?}", from, to);
+		}
+	}
+}
+
+#[test]
+fn it_rejects_illegal_status_transitions_with_specific_errors() {
+	use DocumentStatus::*;
+
+	for to in ALL_STATUSES {
+		assert_eq!(Bhdao::ensure_status_transition(Verified, to), Err(Error::<Test>::DocumentAlreadyFinalized.into()));
+		assert_eq!(Bhdao::ensure_status_transition(Rejected, to), Err(Error::<Test>::DocumentAlreadyFinalized.into()));
+	}
+
+	assert_eq!(Bhdao::ensure_status_transition(UnderReview, UnderReview), Err(Error::<Test>::QualificationVoteAlreadyCreated.into()));
+	assert_eq!(Bhdao::ensure_status_transition(VoteInProgress, VoteInProgress), Err(Error::<Test>::VerificationVoteAlreadyCreated.into()));
+	assert_eq!(Bhdao::ensure_status_transition(SuccessfulReview, UnderReview), Err(Error::<Test>::DocumentNotSubmitted.into()));
+	assert_eq!(Bhdao::ensure_status_transition(VoteInProgress, UnderReview), Err(Error::<Test>::DocumentNotSubmitted.into()));
+	assert_eq!(Bhdao::ensure_status_transition(Submitted, SuccessfulReview), Err(Error::<Test>::DocumentNotUnderReview.into()));
+	assert_eq!(Bhdao::ensure_status_transition(Submitted, VoteInProgress), Err(Error::<Test>::DocumentNotReviewed.into()));
+	assert_eq!(Bhdao::ensure_status_transition(UnderReview, VoteInProgress), Err(Error::<Test>::DocumentNotReviewed.into()));
+	assert_eq!(Bhdao::ensure_status_transition(SuccessfulReview, Verified), Err(Error::<Test>::DocumentNotInVerification.into()));
+	assert_eq!(Bhdao::ensure_status_transition(Submitted, Rejected), Err(Error::<Test>::DocumentNotUnderVote.into()));
+	assert_eq!(Bhdao::ensure_status_transition(SuccessfulReview, Rejected), Err(Error::<Test>::DocumentNotUnderVote.into()));
+	assert_eq!(Bhdao::ensure_status_transition(VoteInProgress, Submitted), Err(Error::<Test>::InvalidDocumentStatusTransition.into()));
+}
+
+#[test]
+fn it_creates_qualification_voting_twice_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::UnderReview);
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(4),1), Error::<Test>::QualificationVoteAlreadyCreated);
+		assert_noop!(Bhdao::create_verification_voting(Origin::signed(4),1), Error::<Test>::DocumentNotReviewed);
+	});
+}
+
+#[test]
+fn it_rejects_document_without_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_quorum(Origin::root(),2u32));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Rejected);
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(4),1), Error::<Test>::DocumentAlreadyFinalized);
+	});
+}
+
+#[test]
+fn it_emits_self_describing_voting_events() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualificationVotingStarted { voting_id: 1, document_id: 1, start: 1, end: 11 }));
+		System::assert_last_event(Event::Bhdao(crate::Event::DocumentStatusUpdated {
+			document_id: 1,
+			from: DocumentStatus::Submitted,
+			to: DocumentStatus::UnderReview,
+		}));
+
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		System::assert_last_event(Event::Bhdao(crate::Event::VoteCast {
+			voter: 4,
+			vote_type: VoteType::Qualification,
+			voting_id: 1,
+			aye: true,
+			note: None,
+		}));
+
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		System::assert_last_event(Event::Bhdao(crate::Event::QualificationVotingEnded {
+			voting_id: 1,
+			document_id: 1,
+			yes_votes: 1,
+			no_votes: 0,
+			outcome: VoteStatus::Passed,
+		}));
+	});
+}
+
+#[test]
+fn it_indexes_votes_by_document() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::set_verification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_eq!(Bhdao::document_qualification_vote(2), None);
+
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),2));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document_votes(1, VoteType::Qualification), vec![2]);
+		assert_eq!(Bhdao::get_document_votes(2, VoteType::Qualification), vec![1]);
+		assert_eq!(Bhdao::current_vote(1, VoteType::Verification), None);
+
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),2,true,None));
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),2));
+		assert_ok!(Bhdao::create_verification_voting(Origin::signed(4),1));
+
+		assert_eq!(Bhdao::current_vote(1, VoteType::Verification), Some(1));
+		let (voting_id, vote) = Bhdao::document_qualification_vote(1).unwrap();
+		assert_eq!(voting_id, 2);
+		assert_eq!(vote.status, VoteStatus::Passed);
+	});
+}
+
+#[test]
+fn it_validates_document_hashes() {
+	assert!(crate::cid::is_valid_cid(CID_1));
+	assert!(crate::cid::is_valid_cid(b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"));
+	assert!(!crate::cid::is_valid_cid(b"https://ipfs.hash"));
+	// Truncated CIDv1 digest
+	assert!(!crate::cid::is_valid_cid(b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbz"));
+	// Base58 alphabet excludes '0'
+	assert!(!crate::cid::is_valid_cid(b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0"));
+	// Oversized CIDv1
+	let mut long = b"b".to_vec();
+	long.extend(sp_std::iter::repeat(b'a').take(crate::cid::MAX_CID_LEN));
+	assert!(!crate::cid::is_valid_cid(&long));
+	// Both versions of a CID name the same content
+	assert_eq!(
+		crate::cid::binary_cid(CID_1),
+		crate::cid::binary_cid(b"bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34"),
+	);
+}
+
+#[test]
+fn it_creates_document_should_fail_for_invalid_or_duplicate_content() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),1));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_noop!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),b"https://ipfs.hash".to_vec()), Error::<Test>::InvalidDocumentHash);
+		assert_noop!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"pdf".to_vec(),CID_1.to_vec()), Error::<Test>::DocumentFormatNotAllowed);
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_eq!(Bhdao::get_document_by_hash(crate::cid::binary_cid(CID_1).unwrap()), Some(1));
+		assert_noop!(Bhdao::create_document(Origin::signed(1),b"Copy".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()), Error::<Test>::DocumentAlreadySubmitted);
+	});
+}
+
+#[test]
+fn it_manages_document_formats() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_noop!(Bhdao::add_document_format(Origin::signed(2),b"model/gltf+json".to_vec()), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Bhdao::add_document_format(Origin::root(),b"model/gltf+json".to_vec()));
+		assert_noop!(Bhdao::add_document_format(Origin::root(),b"model/gltf+json".to_vec()), Error::<Test>::DocumentFormatAlreadyAllowed);
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"model/gltf+json".to_vec(),CID_1.to_vec()));
+
+		assert_ok!(Bhdao::remove_document_format(Origin::root(),b"application/pdf".to_vec()));
+		assert_noop!(Bhdao::remove_document_format(Origin::root(),b"application/pdf".to_vec()), Error::<Test>::DocumentFormatNotFound);
+		assert_noop!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()), Error::<Test>::DocumentFormatNotAllowed);
+	});
+}
+
+#[test]
+fn it_rate_limits_document_submissions() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::set_rate_limits(Origin::root(), RateLimits {
+			max_open_submissions: 2,
+			max_submissions_per_period: 1,
+			max_votes_created_per_period: 1,
+			period: 10,
+		}));
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_noop!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()), Error::<Test>::SubmissionRateLimitExceeded);
+
+		run_to_block(10);
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_eq!(Bhdao::get_account_activity(2).open_submissions, 2);
+		assert_eq!(Bhdao::get_transactions_per_address(2), Some(2));
+		assert_eq!(Bhdao::get_total_transactions(), 2);
+
+		run_to_block(20);
+		assert_noop!(Bhdao::create_document(Origin::signed(2),b"Doc3".to_vec(),b"Test3".to_vec(),b"application/pdf".to_vec(),b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec()), Error::<Test>::TooManyOpenSubmissions);
+	});
+}
+
+#[test]
+fn it_rate_limits_vote_creation_and_releases_open_submissions() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),5u32));
+		assert_ok!(Bhdao::set_rate_limits(Origin::root(), RateLimits {
+			max_open_submissions: 1,
+			max_submissions_per_period: 5,
+			max_votes_created_per_period: 1,
+			period: 100,
+		}));
+		assert_noop!(Bhdao::set_rate_limits(Origin::root(), RateLimits {
+			max_open_submissions: 1,
+			max_submissions_per_period: 5,
+			max_votes_created_per_period: 1,
+			period: 0,
+		}), Error::<Test>::InvalidRateLimits);
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(10);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Rejected);
+		assert_eq!(Bhdao::get_account_activity(2).open_submissions, 0);
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(4),2), Error::<Test>::VoteCreationRateLimitExceeded);
+		assert_eq!(Bhdao::get_account_activity(4).votes_created_in_period, 1);
+	});
+}
+
+#[test]
+fn it_excludes_affiliated_accounts_from_voting() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+
+		// The creator declares qualifier 4 as an affiliate
+		assert_ok!(Bhdao::declare_affiliation(Origin::signed(2),4));
+		assert_noop!(Bhdao::declare_affiliation(Origin::signed(2),4), Error::<Test>::AffiliationAlreadyDeclared);
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(4),1), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(5),1));
+
+		run_to_block(5);
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+
+		run_to_block(20);
+		assert_noop!(Bhdao::finalize_qualification_voting(Origin::signed(4),1), Error::<Test>::ConflictOfInterest);
+
+		assert_noop!(Bhdao::remove_affiliation(Origin::signed(2),2,4), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(Bhdao::remove_affiliation(Origin::root(),4,2), Error::<Test>::AffiliationNotFound);
+		assert_ok!(Bhdao::remove_affiliation(Origin::root(),2,4));
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+
+		// Neither the creator nor its affiliate can open the verification vote
+		assert_ok!(Bhdao::declare_affiliation(Origin::signed(2),4));
+		assert_noop!(Bhdao::create_verification_voting(Origin::signed(4),1), Error::<Test>::ConflictOfInterest);
+		assert_noop!(Bhdao::schedule_voting(Origin::signed(2),VoteType::Verification,1,25), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::create_verification_voting(Origin::signed(5),1));
+	});
+}
+
+#[test]
+fn it_restricts_qualification_voting_to_a_random_panel() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		for qualifier in 10..20 {
+			assert_ok!(Bhdao::add_qualifier(Origin::root(),qualifier));
+		}
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_noop!(Bhdao::set_review_panel_settings(Origin::root(), Some(ReviewPanelSettings { size: 0, quorum: Percent::from_percent(50) })), Error::<Test>::InvalidReviewPanelSettings);
+		assert_ok!(Bhdao::set_review_panel_settings(Origin::root(), Some(ReviewPanelSettings { size: 3, quorum: Percent::from_percent(50) })));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(10),1));
+
+		let panel = Bhdao::get_review_panel(1).unwrap();
+		assert_eq!(panel.members.len(), 3);
+		assert_eq!(panel.quorum, 2);
+		let mut members = panel.members.clone();
+		members.sort();
+		members.dedup();
+		assert_eq!(members.len(), 3);
+
+		run_to_block(5);
+		let outsider = (10..20).find(|q| !panel.members.contains(q)).unwrap();
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(outsider),1,true,None), Error::<Test>::NotAPanelMember);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(panel.members[0]),1,true,None));
+
+		// One ballot out of a panel of three misses the 50% quorum
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(10),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Rejected);
+	});
+}
+
+#[test]
+fn it_draws_review_panel_from_eligible_qualifiers_only() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_review_panel_settings(Origin::root(), Some(ReviewPanelSettings { size: 5, quorum: Percent::from_percent(100) })));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::declare_affiliation(Origin::signed(4),2));
+		// Neither the creator nor its affiliate can open the vote
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(2),1), Error::<Test>::ConflictOfInterest);
+		assert_noop!(Bhdao::create_qualification_voting(Origin::signed(4),1), Error::<Test>::ConflictOfInterest);
+
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(5),1));
+		assert_eq!(Bhdao::get_review_panel(1).unwrap().members, vec![5]);
+		assert_eq!(Bhdao::get_review_panel(1).unwrap().quorum, 1);
+	});
+}
+
+#[test]
+fn it_runs_documents_through_a_custom_pipeline() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),3));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_collector(Origin::root(),5));
+		let stage = |role, quorum, threshold| ReviewStage { role, window: 10, quorum, threshold: Percent::from_percent(threshold) };
+		let stages = vec![
+			stage(Roles::QualifierRole, 1, 50),
+			stage(Roles::CollectorRole, 1, 50),
+			stage(Roles::ContributorRole, 1, 0),
+		];
+		assert_noop!(Bhdao::set_pipeline(Origin::root(),b"image/tiff".to_vec(),vec![]), Error::<Test>::InvalidPipeline);
+		assert_ok!(Bhdao::set_pipeline(Origin::root(),b"image/tiff".to_vec(),stages));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Map".to_vec(),b"Survey map".to_vec(),b"image/tiff".to_vec(),CID_1.to_vec()));
+
+		// Stage 0: qualifiers
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::SuccessfulReview);
+		assert_eq!(Bhdao::get_document_stage(1), 1);
+
+		// Stage 1: collectors
+		assert_ok!(Bhdao::create_verification_voting(Origin::signed(5),1));
+		assert_eq!(Bhdao::get_verification_vote(1).unwrap().role, Roles::CollectorRole);
+		// The pipeline is fixed while the document goes through it
+		assert_noop!(Bhdao::set_pipeline(Origin::root(),b"image/tiff".to_vec(),vec![stage(Roles::QualifierRole, 1, 50)]), Error::<Test>::PipelineInUse);
+		assert_noop!(Bhdao::remove_pipeline(Origin::root(),b"image/tiff".to_vec()), Error::<Test>::PipelineInUse);
+		run_to_block(20);
+		assert_noop!(Bhdao::cast_verification_vote(Origin::signed(3),1,true,None), Error::<Test>::NotACollector);
+		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(5),1,true,None));
+		run_to_block(30);
+		assert_ok!(Bhdao::finalize_verification_voting(Origin::signed(5),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::SuccessfulReview);
+		assert_eq!(Bhdao::get_document_stage(1), 2);
+
+		// Stage 2: contributors, where any yes vote passes
+		assert_ok!(Bhdao::create_verification_voting(Origin::signed(3),1));
+		run_to_block(35);
+		assert_ok!(Bhdao::cast_verification_vote(Origin::signed(3),2,true,None));
+		run_to_block(45);
+		assert_ok!(Bhdao::finalize_verification_voting(Origin::signed(3),2));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Verified);
+
+		assert_ok!(Bhdao::remove_pipeline(Origin::root(),b"image/tiff".to_vec()));
+		assert_noop!(Bhdao::remove_pipeline(Origin::root(),b"image/tiff".to_vec()), Error::<Test>::PipelineNotFound);
+	});
+}
+
+#[test]
+fn it_applies_vote_thresholds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),6));
+		assert_ok!(Bhdao::set_pipeline(Origin::root(),b"application/pdf".to_vec(),vec![
+			ReviewStage { role: Roles::QualifierRole, window: 10, quorum: 0, threshold: Percent::from_percent(66) },
+		]));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(6),1,false,None));
+		run_to_block(15);
+		// 2 of 3 ballots exceed 66%, and the single stage verifies the document
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Verified);
+	});
+}
+
+#[test]
+fn it_migrates_votes_to_review_stages() {
+	use codec::Encode;
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<Bhdao>();
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::set_verification_quorum(Origin::root(),3u32));
+		crate::Documents::<Test>::mutate(1, |document| document.as_mut().unwrap().status = DocumentStatus::VoteInProgress);
+
+		// Verification vote in the layout used before pipelines
+		let old_vote = (1u64, 2u64, 1u64, 10u64, 20u64, VoteStatus::InProgress);
+		frame_support::storage::unhashed::put_raw(&crate::VerificationVotes::<Test>::hashed_key_for(7), &old_vote.encode());
+
+		crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		let vote = Bhdao::get_verification_vote(7).unwrap();
+		assert_eq!((vote.document_id, vote.yes_votes, vote.no_votes, vote.end), (1, 2, 1, 20));
+		assert_eq!((vote.stage, vote.role, vote.quorum), (1, Roles::ContributorRole, 3));
+		assert_eq!(Bhdao::get_document_stage(1), 1);
+		assert_eq!(Bhdao::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn it_counts_delegated_votes_at_tally_time() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		for qualifier in 4..9 {
+			assert_ok!(Bhdao::add_qualifier(Origin::root(),qualifier));
+		}
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+
+		// 6 -> 5 -> 4, and 7 -> 8 where 8 never votes
+		assert_ok!(Bhdao::delegate(Origin::signed(5),VoteType::Qualification,4));
+		assert_ok!(Bhdao::delegate(Origin::signed(6),VoteType::Qualification,5));
+		assert_ok!(Bhdao::delegate(Origin::signed(7),VoteType::Qualification,8));
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,false,None));
+		// A direct ballot overrides the delegation
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+		assert_eq!(Bhdao::resolve_delegated_ballot(&6,VoteType::Qualification,1), Some(true));
+		assert_eq!(Bhdao::resolve_delegated_ballot(&7,VoteType::Qualification,1), None);
+
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		System::assert_has_event(Event::Bhdao(crate::Event::DelegatedVotesCounted {
+			vote_type: VoteType::Qualification,
+			voting_id: 1,
+			yes_votes: 1,
+			no_votes: 0,
+		}));
+		let vote = Bhdao::get_qualification_vote(1).unwrap();
+		assert_eq!((vote.yes_votes, vote.no_votes), (2, 1));
+		assert_eq!(vote.status, VoteStatus::Passed);
+	});
+}
+
+#[test]
+fn it_validates_delegations() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+
+		assert_noop!(Bhdao::delegate(Origin::signed(9),VoteType::Qualification,4), Error::<Test>::NotAMember);
+		assert_noop!(Bhdao::delegate(Origin::signed(4),VoteType::Qualification,4), Error::<Test>::CannotDelegateToSelf);
+		assert_noop!(Bhdao::delegate(Origin::signed(4),VoteType::Qualification,2), Error::<Test>::DelegateRoleMismatch);
+		assert_ok!(Bhdao::delegate(Origin::signed(4),VoteType::Qualification,5));
+		assert_noop!(Bhdao::delegate(Origin::signed(5),VoteType::Qualification,4), Error::<Test>::DelegationCycle);
+		// Delegations are per vote type
+		assert_ok!(Bhdao::delegate(Origin::signed(5),VoteType::Verification,4));
+
+		assert_ok!(Bhdao::undelegate(Origin::signed(4),VoteType::Qualification));
+		assert_eq!(Bhdao::get_delegation(4,VoteType::Qualification), None);
+		assert_noop!(Bhdao::undelegate(Origin::signed(4),VoteType::Qualification), Error::<Test>::DelegationNotFound);
+		assert_ok!(Bhdao::delegate(Origin::signed(5),VoteType::Qualification,4));
+	});
+}
+
+#[test]
+fn it_bounds_delegation_chains_through_the_delegator() {
+	new_test_ext().execute_with(|| {
+		for qualifier in 1..=10 {
+			assert_ok!(Bhdao::add_qualifier(Origin::root(),qualifier));
+		}
+		// 2 -> 3 -> ... -> 9
+		for delegator in 2..=8 {
+			assert_ok!(Bhdao::delegate(Origin::signed(delegator),VoteType::Qualification,delegator + 1));
+		}
+		assert_ok!(Bhdao::delegate(Origin::signed(1),VoteType::Qualification,2));
+		assert_eq!(Bhdao::get_delegators(2,VoteType::Qualification), vec![1]);
+
+		// The chain leading into 9 is already MAX_DELEGATION_DEPTH long
+		assert_noop!(Bhdao::delegate(Origin::signed(9),VoteType::Qualification,10), Error::<Test>::DelegationChainTooLong);
+
+		assert_ok!(Bhdao::undelegate(Origin::signed(1),VoteType::Qualification));
+		assert!(Bhdao::get_delegators(2,VoteType::Qualification).is_empty());
+		assert_ok!(Bhdao::delegate(Origin::signed(9),VoteType::Qualification,10));
+	});
+}
+
+#[test]
+fn it_admits_role_applicants_by_member_vote() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::init_collections(Origin::root()));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),1));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),10u32));
+
+		assert_noop!(Bhdao::apply_for_role(Origin::signed(1),Roles::ContributorRole,CID_1.to_vec()), Error::<Test>::AlreadyAMember);
+		assert_noop!(Bhdao::apply_for_role(Origin::signed(9),Roles::ContributorRole,b"cv.pdf".to_vec()), Error::<Test>::InvalidProfile);
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(9),Roles::ContributorRole,CID_1.to_vec()));
+		assert_noop!(Bhdao::apply_for_role(Origin::signed(9),Roles::ContributorRole,CID_1.to_vec()), Error::<Test>::ApplicationAlreadyPending);
+		assert_eq!(Balances::reserved_balance(9), 1_000);
+
+		run_to_block(5);
+		assert_noop!(Bhdao::cast_application_vote(Origin::signed(4),1,true), Error::<Test>::NotAContributor);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(1),1,true));
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(2),1,true));
+		assert_noop!(Bhdao::finalize_application(Origin::signed(9),1), Error::<Test>::VoteStillInProgress);
+
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_application(Origin::signed(9),1));
+		assert_eq!(Bhdao::get_application(1).unwrap().status, VoteStatus::Passed);
+		assert!(Bhdao::ensure_contributor(9));
+		assert_eq!(Bhdao::contributors_uid_count(), 3);
+		assert_eq!(Balances::reserved_balance(9), 0);
+		assert_eq!(Balances::free_balance(9), 10_000);
+		assert!(Nft::get_token((9, Roles::ContributorRole as u32)).is_some());
+		assert_eq!(Bhdao::get_pending_application(9, Roles::ContributorRole), None);
+	});
+}
+
+#[test]
+fn it_slashes_rejected_and_refunds_withdrawn_applications() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),10u32));
+
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(9),Roles::QualifierRole,CID_1.to_vec()));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(4),1,false));
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_application(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_application(1).unwrap().status, VoteStatus::Failed);
+		assert!(!Bhdao::ensure_qualifier(9));
+		assert_eq!(Balances::free_balance(9), 9_000);
+		assert_eq!(Balances::reserved_balance(9), 0);
+
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(10),Roles::QualifierRole,CID_2.to_vec()));
+		assert_noop!(Bhdao::withdraw_application(Origin::signed(9),2), Error::<Test>::NotAuthorized);
+		assert_ok!(Bhdao::withdraw_application(Origin::signed(10),2));
+		assert_eq!(Bhdao::get_application(2).unwrap().status, VoteStatus::Expired);
+		assert_eq!(Balances::reserved_balance(10), 0);
+		assert_noop!(Bhdao::finalize_application(Origin::signed(4),2), Error::<Test>::ApplicationNotInProgress);
+	});
+}
+
+#[test]
+fn it_refunds_applications_without_a_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),10u32));
+
+		// No collectors yet to vote on the first one
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(9),Roles::CollectorRole,CID_1.to_vec()));
+		assert_ok!(Bhdao::set_application_quorum(Origin::root(),2u32));
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(10),Roles::QualifierRole,CID_2.to_vec()));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(4),2,false));
+		run_to_block(15);
+
+		assert_ok!(Bhdao::finalize_application(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_application(1).unwrap().status, VoteStatus::Expired);
+		assert_eq!(Balances::free_balance(9), 10_000);
+		// One ballot is below the quorum the application was opened with
+		assert_ok!(Bhdao::finalize_application(Origin::signed(4),2));
+		assert_eq!(Bhdao::get_application(2).unwrap().status, VoteStatus::Expired);
+		assert_eq!(Balances::free_balance(10), 10_000);
+		assert_eq!(Balances::reserved_balance(10), 0);
+	});
+}
+
+#[test]
+fn it_refunds_passed_applications_to_a_full_role() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(9),Roles::CollectorRole,CID_1.to_vec()));
+		// The role fills up while the application is open
+		for collector in 100..200 {
+			assert_ok!(Bhdao::add_collector(Origin::root(),collector));
+		}
+		assert_noop!(Bhdao::add_collector(Origin::root(),200), Error::<Test>::TooManyMembers);
+		assert_noop!(Bhdao::add_collector(Origin::root(),100), Error::<Test>::CollectorAlreadyExists);
+
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(100),1,true));
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_application(Origin::signed(100),1));
+		System::assert_has_event(Event::Bhdao(crate::Event::ApplicantNotAdmitted {
+			application_id: 1,
+			error: Error::<Test>::TooManyMembers.into(),
+		}));
+		assert_eq!(Bhdao::get_application(1).unwrap().status, VoteStatus::Expired);
+		assert!(!Bhdao::has_role(&9, Roles::CollectorRole));
+		assert_eq!(Balances::reserved_balance(9), 0);
+		assert_eq!(Bhdao::get_pending_application(9, Roles::CollectorRole), None);
+	});
+}
+
+#[test]
+fn it_sets_member_profiles_with_a_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(3);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),1));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),2));
+
+		assert_noop!(Bhdao::set_profile(Origin::signed(9),b"Ada".to_vec(),b"Archive".to_vec(),CID_1.to_vec()), Error::<Test>::NotAMember);
+		assert_noop!(Bhdao::set_profile(Origin::signed(2),vec![b'a'; 65],b"Archive".to_vec(),CID_1.to_vec()), Error::<Test>::DisplayNameTooLong);
+		assert_noop!(Bhdao::set_profile(Origin::signed(2),b"Ada".to_vec(),b"Archive".to_vec(),b"not a cid".to_vec()), Error::<Test>::InvalidProfile);
+
+		assert_ok!(Bhdao::set_profile(Origin::signed(2),b"Ada".to_vec(),b"Archive".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::set_profile(Origin::signed(2),b"Ada L.".to_vec(),b"Archive".to_vec(),CID_2.to_vec()));
+		assert_eq!(Balances::reserved_balance(2), 100);
+		assert_eq!(Bhdao::get_member_profile(2).unwrap().display_name, b"Ada L.".to_vec());
+
+		let mut memberships = Bhdao::memberships(&2);
+		memberships.sort_by_key(|(role, _)| *role as u32);
+		assert_eq!(memberships.len(), 2);
+		assert_eq!((memberships[0].0, memberships[0].1.uid, memberships[0].1.joined_at), (Roles::QualifierRole, 1, 3));
+		assert_eq!((memberships[1].0, memberships[1].1.uid), (Roles::ContributorRole, 2));
+
+		assert_ok!(Bhdao::clear_profile(Origin::signed(2)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_noop!(Bhdao::clear_profile(Origin::signed(2)), Error::<Test>::ProfileNotFound);
+	});
+}
+
+#[test]
+fn it_expires_qualifier_terms_unless_renewed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::init_collections(Origin::root()));
+		assert_ok!(Bhdao::set_qualifier_term(Origin::root(),10u32));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),5u32));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), Some(11));
+
+		assert_noop!(Bhdao::request_term_renewal(Origin::signed(9),CID_2.to_vec()), Error::<Test>::NoTermToRenew);
+		assert_noop!(Bhdao::request_term_renewal(Origin::signed(4),Vec::new()), Error::<Test>::InvalidProfile);
+		assert_ok!(Bhdao::request_term_renewal(Origin::signed(4),CID_2.to_vec()));
+		run_to_block(3);
+		assert_noop!(Bhdao::cast_application_vote(Origin::signed(4),1,true), Error::<Test>::ConflictOfInterest);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(5),1,true));
+		run_to_block(7);
+		assert_ok!(Bhdao::finalize_application(Origin::signed(5),1));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualifierTermRenewed { who: 4, ends_at: Some(21) }));
+		assert_eq!(Bhdao::qualifiers_uid_count(), 2);
+
+		run_to_block(11);
+		assert!(Bhdao::ensure_qualifier(4));
+		assert!(!Bhdao::ensure_qualifier(5));
+		assert!(Nft::get_token((5, Roles::QualifierRole as u32)).is_none());
+		assert_eq!(Bhdao::get_membership(5, Roles::QualifierRole), None);
+
+		run_to_block(21);
+		assert!(!Bhdao::ensure_qualifier(4));
+		System::assert_last_event(Event::Bhdao(crate::Event::QualifierTermExpired { who: 4 }));
+	});
+}
+
+#[test]
+fn it_suspends_members_who_stop_voting() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		for qualifier in 4..7 {
+			assert_ok!(Bhdao::add_qualifier(Origin::root(),qualifier));
+		}
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_noop!(
+			Bhdao::set_inactivity_policy(Origin::root(),Some(InactivityPolicy { max_missed_votes: 0, grace_period: 10 })),
+			Error::<Test>::InvalidInactivityPolicy
+		);
+		assert_ok!(Bhdao::set_inactivity_policy(Origin::root(),Some(InactivityPolicy { max_missed_votes: 2, grace_period: 10 })));
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_participation(4, Roles::QualifierRole).last_active, 5);
+		assert_eq!(Bhdao::get_participation(5, Roles::QualifierRole).missed_votes, 1);
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),2));
+		run_to_block(17);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),2,true,None));
+		run_to_block(27);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),2));
+		System::assert_has_event(Event::Bhdao(crate::Event::MemberFlaggedInactive {
+			who: 6,
+			role: Roles::QualifierRole,
+			missed_votes: 2,
+			suspend_at: 37,
+		}));
+		assert_eq!(Bhdao::get_participation(5, Roles::QualifierRole).suspend_at, Some(37));
+
+		// Voting on anything as a qualifier lifts the flag
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(9),Roles::QualifierRole,CID_1.to_vec()));
+		run_to_block(28);
+		assert_ok!(Bhdao::cast_application_vote(Origin::signed(5),1,true));
+		System::assert_has_event(Event::Bhdao(crate::Event::MemberReactivated { who: 5, role: Roles::QualifierRole }));
+
+		run_to_block(37);
+		assert!(Bhdao::ensure_qualifier(5));
+		assert!(!Bhdao::ensure_qualifier(6));
+		System::assert_has_event(Event::Bhdao(crate::Event::MemberSuspended { who: 6, role: Roles::QualifierRole }));
+	});
+}
+
+#[test]
+fn it_lifts_inactivity_flags_when_the_policy_is_removed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		let policy = InactivityPolicy { max_missed_votes: 1, grace_period: 10 };
+		assert_ok!(Bhdao::set_inactivity_policy(Origin::root(),Some(policy)));
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_participation(5, Roles::QualifierRole).suspend_at, Some(25));
+
+		assert_ok!(Bhdao::set_inactivity_policy(Origin::root(),None));
+		run_to_block(25);
+		assert!(Bhdao::ensure_qualifier(5));
+		assert_eq!(Bhdao::get_participation(5, Roles::QualifierRole).suspend_at, None);
+
+		// Flagged again under a new policy
+		assert_ok!(Bhdao::set_inactivity_policy(Origin::root(),Some(policy)));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),2));
+		run_to_block(26);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),2,true,None));
+		run_to_block(36);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),2));
+		assert_eq!(Bhdao::get_participation(5, Roles::QualifierRole).suspend_at, Some(46));
+	});
+}
+
+#[test]
+fn it_extends_and_cancels_in_flight_votes() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::set_review_panel_settings(Origin::root(), Some(ReviewPanelSettings { size: 1, quorum: Percent::from_percent(100) })));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Unavailable));
+		assert!(Bhdao::get_review_panel(1).is_some());
+
+		// Changing the global settings leaves the open vote alone
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),100u32));
+		assert_ok!(Bhdao::set_qualification_quorum(Origin::root(),50u32));
+		let vote = Bhdao::get_qualification_vote(1).unwrap();
+		assert_eq!((vote.end, vote.quorum), (11, 1));
+
+		assert_noop!(Bhdao::extend_vote(Origin::signed(4),VoteType::Qualification,1,5), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Bhdao::extend_vote(Origin::root(),VoteType::Qualification,1,5));
+		System::assert_last_event(Event::Bhdao(crate::Event::VoteExtended { vote_type: VoteType::Qualification, voting_id: 1, from: 11, to: 16 }));
+		assert_eq!(Bhdao::get_qualification_vote(1).unwrap().end, 16);
+
+		assert_ok!(Bhdao::cancel_vote(Origin::root(),VoteType::Qualification,1));
+		System::assert_last_event(Event::Bhdao(crate::Event::VoteCancelled { vote_type: VoteType::Qualification, voting_id: 1 }));
+		assert_eq!(Bhdao::get_qualification_vote(1).unwrap().status, VoteStatus::Cancelled);
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Submitted);
+		// Nothing keeps checking or reviewing a document without an open vote
+		assert_eq!(Bhdao::get_pending_availability(1), None);
+		assert_eq!(Bhdao::get_availability_attempts(1), 0);
+		assert_eq!(Bhdao::get_review_panel(1), None);
+		assert_noop!(Bhdao::cancel_vote(Origin::root(),VoteType::Qualification,1), Error::<Test>::VoteNotInProgress);
+		assert_noop!(Bhdao::extend_vote(Origin::root(),VoteType::Qualification,1,5), Error::<Test>::VoteNotInProgress);
+
+		// A new vote can be opened after the cancellation
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::current_vote(1, VoteType::Qualification), Some(2));
+
+		// Once the window has closed the vote can no longer be extended
+		run_to_block(101);
+		assert_noop!(Bhdao::extend_vote(Origin::root(),VoteType::Qualification,2,5), Error::<Test>::VotingWindowNotValid);
+	});
+}
+
+#[test]
+fn it_filters_dao_calls_while_paused() {
+	use sp_runtime::traits::Dispatchable;
+
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+
+		assert_noop!(Bhdao::set_paused(Origin::signed(4),true), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Bhdao::set_paused(Origin::root(),true));
+		System::assert_last_event(Event::Bhdao(crate::Event::PauseChanged { paused: true }));
+
+		let vote = Call::Bhdao(crate::Call::cast_qualification_vote { voting_id: 1, vote_cast: true, note: None });
+		assert_noop!(vote.dispatch(Origin::signed(4)), frame_system::Error::<Test>::CallFiltered);
+		let burn = Call::Nft(pallet_nft::Call::burn { collection_id: Roles::QualifierRole as u32 });
+		assert_noop!(burn.dispatch(Origin::signed(4)), frame_system::Error::<Test>::CallFiltered);
+
+		let cancel = Call::Bhdao(crate::Call::cancel_vote { vote_type: VoteType::Qualification, voting_id: 1 });
+		assert_ok!(cancel.dispatch(Origin::root()));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Submitted);
+
+		assert_ok!(Call::Bhdao(crate::Call::set_paused { paused: false }).dispatch(Origin::root()));
+		let create = Call::Bhdao(crate::Call::create_qualification_voting { document_id: 1 });
+		assert_ok!(create.dispatch(Origin::signed(4)));
+	});
+}
+
+#[test]
+fn it_gives_existing_qualifiers_a_term() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::init_collections(Origin::root()));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),5u32));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), None);
+
+		run_to_block(2);
+		assert_ok!(Bhdao::set_qualifier_term(Origin::root(),10u32));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), Some(12));
+
+		// A lone qualifier's renewal has no voters and costs nothing
+		assert_ok!(Bhdao::request_term_renewal(Origin::signed(4),CID_2.to_vec()));
+		run_to_block(8);
+		assert_ok!(Bhdao::finalize_application(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_application(1).unwrap().status, VoteStatus::Expired);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 10_000);
+
+		assert_ok!(Bhdao::set_qualifier_term(Origin::root(),0u32));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), None);
+	});
+}
+
+#[test]
+fn it_postpones_member_deadlines_over_a_pause() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::init_collections(Origin::root()));
+		assert_ok!(Bhdao::set_qualifier_term(Origin::root(),10u32));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), Some(11));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::set_application_voting_window(Origin::root(),5u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_ok!(Bhdao::apply_for_role(Origin::signed(6),Roles::CollectorRole,CID_2.to_vec()));
+
+		run_to_block(5);
+		assert_ok!(Bhdao::set_paused(Origin::root(),true));
+		run_to_block(15);
+		// The term would have ended during the pause
+		assert!(Bhdao::ensure_qualifier(4));
+
+		assert_ok!(Bhdao::set_paused(Origin::root(),false));
+		assert_eq!(Bhdao::get_qualifier_term_end(4), Some(21));
+		// Open windows get the paused blocks back
+		let vote = Bhdao::get_qualification_vote(1).unwrap();
+		assert_eq!((vote.start, vote.end), (1, 21));
+		let application = Bhdao::get_application(1).unwrap();
+		assert_eq!((application.start, application.end), (1, 16));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(20);
+		assert!(Bhdao::ensure_qualifier(4));
+		run_to_block(21);
+		assert!(!Bhdao::ensure_qualifier(4));
+	});
+}
+
+#[test]
+fn it_attests_document_availability_offchain() {
+	use codec::{Decode, Encode};
+	use frame_support::traits::OffchainWorker;
+	use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt};
+	use sp_runtime::testing::UintAuthorityId;
+
+	let mut ext = new_test_ext();
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![4u64]);
+
+	ext.execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_pending_availability(1), Some(1));
+
+		// A stand-in for the node's local gateway
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, crate::ipfs::GATEWAY_KEY, &b"http://localhost:5001/".to_vec().encode());
+		offchain_state.write().expect_request(testing::PendingRequest {
+			method: "HEAD".into(),
+			uri: format!("http://localhost:5001/ipfs/{}", std::str::from_utf8(CID_1).unwrap()),
+			response: Some(vec![]),
+			response_headers: vec![
+				("content-type".into(), "application/pdf".into()),
+				("content-length".into(), "1024".into()),
+			],
+			sent: true,
+			..Default::default()
+		});
+		Bhdao::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(tx.call, Call::Bhdao(crate::Call::submit_availability {
+			document_id: 1,
+			availability: Availability::Available { size: 1024 },
+		}));
+
+		assert_noop!(Bhdao::submit_availability(Origin::signed(9),1,Availability::Unavailable), Error::<Test>::NotAQualifier);
+		assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Available { size: 1024 }));
+		assert_eq!(Bhdao::get_pending_availability(1), None);
+		assert_eq!(Bhdao::get_availability(1).unwrap().attester, 4);
+	});
+}
+
+#[test]
+fn it_retries_inconclusive_availability_checks() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+
+		// Affiliates of the creator cannot attest
+		assert_ok!(Bhdao::declare_affiliation(Origin::signed(2),5));
+		assert_noop!(Bhdao::submit_availability(Origin::signed(5),1,Availability::Available { size: 1024 }), Error::<Test>::ConflictOfInterest);
+
+		for _ in 1..crate::MAX_AVAILABILITY_ATTEMPTS {
+			assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Unavailable));
+		}
+		assert_eq!(Bhdao::get_pending_availability(1), Some(1));
+		assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Unavailable));
+		System::assert_last_event(Event::Bhdao(crate::Event::AvailabilityAttested {
+			document_id: 1,
+			attester: 4,
+			availability: Availability::Unavailable,
+			replaced: Some(Availability::Unavailable),
+		}));
+
+		// The attempts are exhausted and the result can no longer be replaced
+		assert_eq!(Bhdao::get_pending_availability(1), None);
+		assert_noop!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Available { size: 1024 }), Error::<Test>::AvailabilityNotPending);
+	});
+}
+
+#[test]
+fn it_blocks_verification_of_unavailable_documents() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_ok!(Bhdao::set_availability_required(Origin::root(),true));
+
+		assert_noop!(Bhdao::update_document_status(1, DocumentStatus::Verified), Error::<Test>::DocumentNotAvailable);
+		assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::FormatMismatch));
+		assert_noop!(Bhdao::update_document_status(1, DocumentStatus::Verified), Error::<Test>::DocumentNotAvailable);
+
+		assert_ok!(Bhdao::submit_availability(Origin::signed(4),1,Availability::Available { size: 1024 }));
+		assert_ok!(Bhdao::update_document_status(1, DocumentStatus::Verified));
+	});
+}
+
+#[test]
+fn it_rejects_unavailable_documents_when_their_last_vote_passes() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		let stage = ReviewStage { role: Roles::QualifierRole, window: 10, quorum: 1, threshold: Percent::from_percent(50) };
+		assert_ok!(Bhdao::set_pipeline(Origin::root(),b"image/tiff".to_vec(),vec![stage]));
+		assert_ok!(Bhdao::set_availability_required(Origin::root(),true));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Map".to_vec(),b"Survey map".to_vec(),b"image/tiff".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+
+		// The vote passes, but the content was never attested as available
+		run_to_block(15);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		System::assert_has_event(Event::Bhdao(crate::Event::DocumentRejectedAsUnavailable { document_id: 1 }));
+		assert_eq!(Bhdao::get_qualification_vote(1).unwrap().status, VoteStatus::Failed);
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::Rejected);
+		assert_eq!(Bhdao::get_pending_availability(1), None);
+	});
+}
+
+#[test]
+fn it_attaches_review_notes_to_ballots() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		for qualifier in 4..7 {
+			assert_ok!(Bhdao::add_qualifier(Origin::root(),qualifier));
+		}
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+
+		let too_long = ReviewNote::Text(vec![b'a'; crate::MAX_REVIEW_NOTE_LEN + 1]);
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(4),1,false,Some(too_long)), Error::<Test>::ReviewNoteTooLong);
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(4),1,false,Some(ReviewNote::Cid(b"review".to_vec()))), Error::<Test>::InvalidReviewNote);
+		let oversized = ReviewNote::Cid([CID_2, &[b'a'; crate::cid::MAX_CID_LEN][..]].concat());
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(4),1,false,Some(oversized)), Error::<Test>::InvalidReviewNote);
+
+		let note = ReviewNote::Text(b"Scan is missing pages 4-7".to_vec());
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,false,Some(note.clone())));
+		System::assert_last_event(Event::Bhdao(crate::Event::VoteCast {
+			voter: 4,
+			vote_type: VoteType::Qualification,
+			voting_id: 1,
+			aye: false,
+			note: Some(note.clone()),
+		}));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,Some(ReviewNote::Cid(CID_2.to_vec()))));
+
+		// Only two ballots per vote may carry a note in the mock runtime
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(6),1,true,Some(note.clone())), Error::<Test>::TooManyReviewNotes);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(6),1,true,None));
+
+		assert_eq!(Bhdao::get_review_note((4, VoteType::Qualification, 1)), Some(note.clone()));
+		assert_eq!(Bhdao::review_notes(VoteType::Qualification, 1), vec![
+			(4, false, note),
+			(5, true, ReviewNote::Cid(CID_2.to_vec())),
+		]);
+	});
+}
+
+#[test]
+fn it_indexes_documents_by_category_tag_creator_and_status() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),3));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_noop!(Bhdao::add_category(Origin::root(),Vec::new()), Error::<Test>::InvalidCategoryName);
+		assert_ok!(Bhdao::add_category(Origin::root(),b"manuscripts".to_vec()));
+		assert_ok!(Bhdao::add_category(Origin::root(),b"maps".to_vec()));
+
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(3),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+
+		assert_noop!(Bhdao::classify_document(Origin::signed(3),1,Some(1),Vec::new()), Error::<Test>::NotAuthorized);
+		assert_noop!(Bhdao::classify_document(Origin::signed(2),1,Some(9),Vec::new()), Error::<Test>::CategoryNotFound);
+		assert_noop!(Bhdao::classify_document(Origin::signed(2),1,Some(1),vec![b"ottoman".to_vec(), b"ottoman".to_vec()]), Error::<Test>::DuplicateTag);
+		assert_noop!(Bhdao::classify_document(Origin::signed(2),1,Some(1),vec![Vec::new()]), Error::<Test>::InvalidTag);
+		assert_ok!(Bhdao::classify_document(Origin::signed(2),1,Some(2),vec![b"ottoman".to_vec()]));
+		assert_ok!(Bhdao::classify_document(Origin::signed(2),1,Some(1),vec![b"ottoman".to_vec(), b"1800s".to_vec()]));
+		assert_ok!(Bhdao::classify_document(Origin::signed(3),2,Some(1),vec![b"1800s".to_vec()]));
+
+		assert_eq!(Bhdao::documents_in_category(2, None, None, 10), Vec::<u64>::new());
+		let mut in_category = Bhdao::documents_in_category(1, None, None, 10);
+		in_category.sort();
+		assert_eq!(in_category, vec![1, 2]);
+		let mut tagged = Bhdao::documents_with_tag(b"1800s", None, 10);
+		tagged.sort();
+		assert_eq!(tagged, vec![1, 2]);
+		assert_eq!(Bhdao::documents_with_tag(b"ottoman", None, 10), vec![1]);
+		// Pages follow the last document of the previous one
+		let first = Bhdao::documents_with_tag(b"1800s", None, 1);
+		let second = Bhdao::documents_with_tag(b"1800s", Some(first[0]), 1);
+		assert_eq!((first.len(), second.len()), (1, 1));
+		assert_ne!(first, second);
+		assert_eq!(Bhdao::documents_with_tag(b"1800s", Some(second[0]), 1), Vec::<u64>::new());
+		let first = Bhdao::documents_in_category(1, None, None, 1);
+		let second = Bhdao::documents_in_category(1, None, Some(first[0]), 10);
+		assert_eq!(second.len(), 1);
+		assert_ne!(first, second);
+		assert_eq!(Bhdao::documents_by_creator(&3, None, 10), vec![2]);
+
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::documents_in_status(DocumentStatus::Submitted, None, 10), vec![2]);
+		assert_eq!(Bhdao::documents_in_status(DocumentStatus::UnderReview, None, 10), vec![1]);
+		assert_eq!(Bhdao::documents_in_category(1, Some(DocumentStatus::UnderReview), None, 10), vec![1]);
+		assert_eq!(Bhdao::documents_in_category(1, Some(DocumentStatus::Submitted), None, 10), vec![2]);
+
+		assert_noop!(Bhdao::remove_category(Origin::root(),1), Error::<Test>::CategoryNotEmpty);
+		assert_ok!(Bhdao::remove_category(Origin::root(),2));
+	});
+}
+
+#[test]
+fn it_migrates_document_indexes() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		let _ = crate::DocumentsByCreator::<Test>::clear(u32::MAX, None);
+		let _ = crate::DocumentsByStatus::<Test>::clear(u32::MAX, None);
+		StorageVersion::new(1).put::<Bhdao>();
+
+		crate::migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Bhdao::documents_by_creator(&2, None, 10), vec![1]);
+		assert_eq!(Bhdao::documents_in_status(DocumentStatus::Submitted, None, 10), vec![1]);
+		assert_eq!(Bhdao::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn it_backfills_existing_documents_and_members() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bhdao::init_collections(Origin::root()));
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		// Documents submitted before the content index, the second one a duplicate of the first
+		crate::Documents::<Test>::mutate(2, |document| {
+			document.as_mut().unwrap().hash = b"bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34".to_vec();
+		});
+		let _ = crate::DocumentsByHash::<Test>::clear(u32::MAX, None);
+		crate::DocumentTimelines::<Test>::remove(1);
+		crate::Memberships::<Test>::remove(2, Roles::ContributorRole);
+		StorageVersion::new(2).put::<Bhdao>();
+
+		crate::migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+		// Documents submitted before timelines get one starting at block 0
+		let timeline = Bhdao::get_document_timeline(1).unwrap();
+		assert_eq!((timeline.created.block, timeline.updated.block, timeline.verified), (0, 0, None));
+		// Members added before memberships get one
+		assert_eq!(Bhdao::get_membership(2, Roles::ContributorRole), Some(crate::Membership { uid: 1, joined_at: 0 }));
+
+		// The earliest submission of some content keeps it
+		assert_eq!(Bhdao::get_document_by_hash(crate::cid::binary_cid(CID_1).unwrap()), Some(1));
+		assert_noop!(
+			Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),b"bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34".to_vec()),
+			Error::<Test>::DocumentAlreadySubmitted
+		);
+		assert_eq!(Bhdao::on_chain_storage_version(), 3);
+	});
+}
+
+#[test]
+fn it_locks_document_rights_at_verification() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+
+		let rights = RightsMetadata::<Test> {
+			license: b"CC-BY-4.0".to_vec(),
+			rights_holder: b"City Archive".to_vec(),
+			provenance: vec![b"Private collection, 1902".to_vec(), b"Donated to the City Archive, 1958".to_vec()],
+			embargo_until: Some(10),
+		};
+		let unlicensed = RightsMetadata::<Test> { license: b"All rights reserved".to_vec(), ..rights.clone() };
+		assert_noop!(Bhdao::set_document_rights(Origin::signed(2),1,unlicensed.clone()), Error::<Test>::LicenseNotAllowed);
+		assert_noop!(Bhdao::set_document_rights(Origin::signed(3),1,rights.clone()), Error::<Test>::NotAuthorized);
+		assert_noop!(
+			Bhdao::set_document_rights(Origin::signed(2),1,RightsMetadata { provenance: vec![Vec::new()], ..rights.clone() }),
+			Error::<Test>::InvalidProvenance
+		);
+
+		assert_noop!(Bhdao::add_license(Origin::root(),vec![b'a'; crate::MAX_LICENSE_LEN + 1]), Error::<Test>::LicenseTooLong);
+		assert_ok!(Bhdao::add_license(Origin::root(),b"All rights reserved".to_vec()));
+		assert_ok!(Bhdao::set_document_rights(Origin::signed(2),1,unlicensed));
+		assert_ok!(Bhdao::set_document_rights(Origin::signed(2),1,rights.clone()));
+		assert_eq!(Bhdao::get_document_rights(1), Some(rights.clone()));
+		assert!(Bhdao::is_embargoed(1));
+
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_ok!(Bhdao::update_document_status(1, DocumentStatus::Verified));
+		assert_noop!(Bhdao::set_document_rights(Origin::signed(2),1,rights), Error::<Test>::RightsLocked);
+
+		run_to_block(10);
+		assert!(!Bhdao::is_embargoed(1));
+	});
+}
+
+#[test]
+fn it_records_document_and_vote_timelines() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_pipeline(Origin::root(),b"application/pdf".to_vec(),vec![ReviewStage {
+			role: Roles::QualifierRole,
+			window: 10,
+			quorum: 1,
+			threshold: Percent::from_percent(50),
+		}]));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		let timeline = Bhdao::get_document_timeline(1).unwrap();
+		assert_eq!((timeline.created.block, timeline.created.time), (1, 6_000));
+		assert_eq!(timeline.updated, timeline.created);
+
+		run_to_block(3);
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		assert_eq!(Bhdao::get_document_timeline(1).unwrap().updated.block, 3);
+
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+
+		let timeline = Bhdao::get_document_timeline(1).unwrap();
+		assert_eq!(timeline.created.block, 1);
+		let verified = timeline.verified.unwrap();
+		assert_eq!((verified.block, verified.time), (20, 120_000));
+		let finalization = Bhdao::get_finalization((VoteType::Qualification, 1)).unwrap();
+		assert_eq!((finalization.finalized.block, finalization.finalizer), (20, 4));
+	});
+}
+
+#[test]
+fn it_prunes_finished_votes_after_the_retention_period() {
+	use frame_support::{traits::OnIdle, weights::Weight};
+
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,Some(ReviewNote::Text(b"Legible".to_vec()))));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+
+		// Still within the retention period
+		Bhdao::on_idle(29, Weight::MAX);
+		assert!(Bhdao::get_qualification_vote(1).is_some());
+
+		Bhdao::on_idle(30, Weight::MAX);
+		System::assert_last_event(Event::Bhdao(crate::Event::VotePruned { vote_type: VoteType::Qualification, voting_id: 1, document_id: 1 }));
+		assert!(Bhdao::get_qualification_vote(1).is_none());
+		assert_eq!(Bhdao::get_member_vote((4, VoteType::Qualification, 1)), None);
+		assert_eq!(Bhdao::get_member_vote((5, VoteType::Qualification, 1)), None);
+		assert_eq!(Bhdao::get_review_note((4, VoteType::Qualification, 1)), None);
+		// The document keeps the history of its votes
+		assert_eq!(Bhdao::get_document_votes(1, VoteType::Qualification), vec![1]);
+		assert_eq!(Bhdao::get_vote_summaries(1), vec![VoteSummary {
+			vote_type: VoteType::Qualification,
+			voting_id: 1,
+			stage: 0,
+			yes_votes: 2,
+			no_votes: 0,
+			outcome: VoteStatus::Passed,
+		}]);
+
+		// Nothing left to prune
+		Bhdao::on_idle(40, Weight::MAX);
+		assert_eq!(Bhdao::get_vote_summaries(1).len(), 1);
+	});
+}
+
+#[test]
+fn it_migrates_votes_finished_before_pruning() {
+	use frame_support::{traits::{OnIdle, OnRuntimeUpgrade, StorageVersion}, weights::Weight};
+
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),5));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),1));
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(5),1,true,None));
+		run_to_block(20);
+		assert_ok!(Bhdao::finalize_qualification_voting(Origin::signed(4),1));
+		// The vote as it was left before pruning existed
+		let _ = crate::PruneQueue::<Test>::clear(u32::MAX, None);
+		crate::PruneQueueBounds::<Test>::kill();
+		crate::VoteBallots::<Test>::remove((VoteType::Qualification, 1));
+		StorageVersion::new(2).put::<Bhdao>();
+
+		crate::migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+		Bhdao::on_idle(30, Weight::MAX);
+		System::assert_last_event(Event::Bhdao(crate::Event::VotePruned { vote_type: VoteType::Qualification, voting_id: 1, document_id: 1 }));
+		assert_eq!(Bhdao::get_member_vote((4, VoteType::Qualification, 1)), None);
+		assert_eq!(Bhdao::get_member_vote((5, VoteType::Qualification, 1)), None);
+		// The document keeps the history of its votes
+		assert_eq!(Bhdao::get_document_votes(1, VoteType::Qualification), vec![1]);
+	});
+}
+
+#[test]
+fn it_schedules_votes_to_open_at_a_future_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+
+		run_to_block(3);
+		assert_noop!(Bhdao::schedule_voting(Origin::signed(4),VoteType::Qualification,1,2), Error::<Test>::VoteStartInPast);
+		assert_noop!(Bhdao::schedule_voting(Origin::signed(4),VoteType::Application,1,5), Error::<Test>::InvalidVoteType);
+		assert_ok!(Bhdao::schedule_voting(Origin::signed(4),VoteType::Qualification,1,5));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualificationVotingStarted { voting_id: 1, document_id: 1, start: 5, end: 15 }));
+
+		run_to_block(4);
+		assert_noop!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None), Error::<Test>::VotingWindowNotValid);
+		// Ballots are accepted from the opening block itself
+		run_to_block(5);
+		assert_ok!(Bhdao::cast_qualification_vote(Origin::signed(4),1,true,None));
+	});
+}
+
+#[test]
+fn it_creates_qualification_votes_in_batches() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		assert_ok!(Bhdao::add_contributor(Origin::root(),2));
+		assert_ok!(Bhdao::add_qualifier(Origin::root(),4));
+		assert_ok!(Bhdao::set_qualification_voting_window(Origin::root(),10u32));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc1".to_vec(),b"Test1".to_vec(),b"application/pdf".to_vec(),CID_1.to_vec()));
+		assert_ok!(Bhdao::create_document(Origin::signed(2),b"Doc2".to_vec(),b"Test2".to_vec(),b"application/pdf".to_vec(),CID_2.to_vec()));
+		assert_ok!(Bhdao::create_qualification_voting(Origin::signed(4),2));
+
+		assert_noop!(Bhdao::create_qualification_votings(Origin::signed(4),vec![1; 33],None), Error::<Test>::TooManyDocuments);
+		assert_ok!(Bhdao::create_qualification_votings(Origin::signed(4),vec![1, 2, 9],Some(4)));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualificationVotingStarted { voting_id: 2, document_id: 1, start: 4, end: 14 }));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualificationVotingNotCreated {
+			document_id: 2,
+			error: Error::<Test>::QualificationVoteAlreadyCreated.into(),
+		}));
+		System::assert_has_event(Event::Bhdao(crate::Event::QualificationVotingNotCreated {
+			document_id: 9,
+			error: Error::<Test>::DocumentNotFound.into(),
+		}));
+		System::assert_last_event(Event::Bhdao(crate::Event::QualificationVotingsCreated { created: 1, failed: 2 }));
+		assert_eq!(Bhdao::get_document(1).unwrap().status, DocumentStatus::UnderReview);
+		assert_eq!(Bhdao::get_qualification_vote_count(), 2);
+	});
+}
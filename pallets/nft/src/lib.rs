@@ -8,10 +8,15 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
-    use frame_system::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency},
+	};
+	use frame_system::{ensure_signed_or_root, pallet_prelude::*};
 	use scale_info::TypeInfo;
 
 	use sp_runtime::ArithmeticError;
@@ -20,13 +25,44 @@ pub mod pallet {
 	#[cfg(feature = "std")]
 	use frame_support::serde::{Deserialize, Serialize};
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+	pub(crate) type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
     #[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Reserved from an owner setting the metadata of a token.
+		#[pallet::constant]
+		type MetadataDepositBase: Get<BalanceOf<Self>>;
+
+		/// Reserved from an owner setting an attribute of a token.
+		#[pallet::constant]
+		type AttributeDepositBase: Get<BalanceOf<Self>>;
+
+		/// Additionally reserved per byte of metadata, attribute key and attribute value.
+		#[pallet::constant]
+		type DepositPerByte: Get<BalanceOf<Self>>;
+
+		/// Maximum length of the metadata of a token.
+		#[pallet::constant]
+		type StringLimit: Get<u32>;
+
+		/// Maximum length of an attribute key.
+		#[pallet::constant]
+		type KeyLimit: Get<u32>;
+
+		/// Maximum length of an attribute value.
+		#[pallet::constant]
+		type ValueLimit: Get<u32>;
 	}
 
     #[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -37,6 +73,9 @@ pub mod pallet {
 		pub total_supply: u32,
 		pub created_at: T::BlockNumber,
 		pub metadata: Vec<u8>,
+		/// Whether tokens can change hands. Soulbound collections, such as the role badges,
+		/// can only be minted and burned.
+		pub transferable: bool,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
@@ -46,12 +85,68 @@ pub mod pallet {
 		pub owner: T::AccountId,
 	}
 
+	/// Who may set the metadata and attributes of the tokens of a collection, besides root.
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	pub enum MetadataPolicy {
+		/// Only the collection admin.
+		Admin,
+		/// The holder of each token too.
+		Owner,
+	}
+
+	impl Default for MetadataPolicy {
+		fn default() -> Self {
+			MetadataPolicy::Admin
+		}
+	}
+
+	/// Funds reserved for an item of token data.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Deposit<T:Config> {
+		pub depositor: T::AccountId,
+		pub amount: BalanceOf<T>,
+	}
+
+	/// URI or CID describing a token.
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct TokenMetadata<T:Config> {
+		pub data: BoundedVec<u8, T::StringLimit>,
+		/// None when set by the admin.
+		pub deposit: Option<Deposit<T>>,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Debug, TypeInfo, Eq)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Attribute<T:Config> {
+		pub value: BoundedVec<u8, T::ValueLimit>,
+		/// None when set by the admin.
+		pub deposit: Option<Deposit<T>>,
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		CollectionCreated(u32),
 		NFTMinted(u32, u32, T::AccountId),
 		NFTBurned(u32, u32, T::AccountId),		
+		/// collection, token, from, to
+		NFTTransferred(u32, u32, T::AccountId, T::AccountId),
+		/// collection, token, owner, delegate
+		TransferApproved(u32, u32, T::AccountId, T::AccountId),
+		/// collection, token, owner
+		ApprovalCancelled(u32, u32, T::AccountId),
+		/// collection, policy
+		MetadataPolicySet(u32, MetadataPolicy),
+		/// collection, token, data
+		TokenMetadataSet(u32, u32, Vec<u8>),
+		/// collection, token
+		TokenMetadataCleared(u32, u32),
+		/// collection, token, key, value
+		AttributeSet(u32, u32, Vec<u8>, Vec<u8>),
+		/// collection, token, key
+		AttributeCleared(u32, u32, Vec<u8>),
 	}
 
 	// Errors inform users that something went wrong.
@@ -65,6 +160,13 @@ pub mod pallet {
 		TokenMaxSupplyReached,
 		TokenDoesNotExist,
 		NullValue,
+		CollectionNotTransferable,
+		TransferToSelf,
+		NotApproved,
+		NoPermission,
+		MetadataNotFound,
+		AttributeNotFound,
+		LockedByAdmin,
 	}
 
 	#[pallet::storage]
@@ -111,11 +213,84 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Holder of each token, keyed by collection and token id.
+	#[pallet::storage]
+	#[pallet::getter(fn get_owner)]
+	pub(super) type Owners<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u32,
+		Blake2_128Concat,
+		u32,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	/// Id of the token an account holds in each collection.
+	#[pallet::storage]
+	#[pallet::getter(fn get_account_token)]
+	pub(super) type AccountTokens<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		u32,
+		u32,
+		OptionQuery,
+	>;
+
+	/// The account allowed to transfer the token an owner holds in a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn get_approval)]
+	pub(super) type Approvals<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId,u32),
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_metadata_policy)]
+	pub(super) type MetadataPolicies<T:Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		u32,
+		MetadataPolicy,
+		ValueQuery,
+	>;
+
+	/// Metadata of a token, keyed by collection and token id. It stays with the token across
+	/// transfers.
+	#[pallet::storage]
+	#[pallet::getter(fn get_token_metadata)]
+	pub(super) type TokensMetadata<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u32,
+		Blake2_128Concat,
+		u32,
+		TokenMetadata<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_attribute)]
+	pub(super) type Attributes<T:Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(u32,u32),
+		Blake2_128Concat,
+		BoundedVec<u8, T::KeyLimit>,
+		Attribute<T>,
+		OptionQuery,
+	>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
 		#[pallet::weight(10_000)]
-		pub fn create_collection(origin: OriginFor<T>,uid: u32, total_supply: u32, metadata: Vec<u8>) -> DispatchResult {
+		pub fn create_collection(origin: OriginFor<T>,uid: u32, total_supply: u32, metadata: Vec<u8>, transferable: bool) -> DispatchResult {
 			ensure_root(origin)?;// Temporary
 			//let who = ensure_signed(origin)?;
 			ensure!(!Collections::<T>::contains_key(uid.clone()),Error::<T>::CollectionExists);
@@ -125,6 +300,7 @@ pub mod pallet {
 				total_supply: total_supply,
 				created_at: now,
 				metadata: metadata,
+				transferable: transferable,
 			};
 
 			Collections::<T>::insert(uid.clone(),&collection);
@@ -155,6 +331,8 @@ pub mod pallet {
 			};
 
 			Tokens::<T>::insert((who.clone(),collection_id.clone()),token);
+			Owners::<T>::insert(collection_id,uid,&who);
+			AccountTokens::<T>::insert(&who,collection_id,uid);
 			active = active + 1;
 			ActiveTokens::<T>::insert(collection_id.clone(),active);
 			TotalTokens::<T>::insert(collection_id.clone(),uid.clone());
@@ -167,7 +345,136 @@ pub mod pallet {
 		#[pallet::weight(10_000)]
 		pub fn burn(origin: OriginFor<T>,collection_id: u32) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			
+			Self::do_burn(who, collection_id)
+		}
+
+		/// Sends the caller's token in `collection_id` to `to`.
+		#[pallet::weight(10_000)]
+		pub fn transfer(origin: OriginFor<T>, collection_id: u32, to: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_transfer(who, collection_id, to)
+		}
+
+		/// Lets `delegate` transfer the caller's token in `collection_id`, or cancels the current
+		/// approval when `None`.
+		#[pallet::weight(10_000)]
+		pub fn approve(origin: OriginFor<T>, collection_id: u32, delegate: Option<T::AccountId>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let collection = Self::get_collection(collection_id).ok_or(Error::<T>::CollectionDoesNotExist)?;
+			ensure!(collection.transferable,Error::<T>::CollectionNotTransferable);
+			let token = Self::get_token((who.clone(),collection_id)).ok_or(Error::<T>::TokenDoesNotExist)?;
+
+			match delegate {
+				Some(delegate) => {
+					Approvals::<T>::insert((who.clone(),collection_id),&delegate);
+					Self::deposit_event(Event::TransferApproved(collection_id,token.id,who,delegate));
+				},
+				None => {
+					Approvals::<T>::remove((who.clone(),collection_id));
+					Self::deposit_event(Event::ApprovalCancelled(collection_id,token.id,who));
+				},
+			}
+
+			Ok(())
+		}
+
+		/// Sends the token `from` holds in `collection_id` to `to` on their behalf.
+		#[pallet::weight(10_000)]
+		pub fn transfer_from(origin: OriginFor<T>, collection_id: u32, from: T::AccountId, to: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::get_approval((from.clone(),collection_id)) == Some(who),Error::<T>::NotApproved);
+			Self::do_transfer(from, collection_id, to)
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn set_metadata_policy(origin: OriginFor<T>, collection_id: u32, policy: MetadataPolicy) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Collections::<T>::contains_key(collection_id),Error::<T>::CollectionDoesNotExist);
+
+			MetadataPolicies::<T>::insert(collection_id,policy);
+			Self::deposit_event(Event::MetadataPolicySet(collection_id,policy));
+
+			Ok(())
+		}
+
+		/// Sets the metadata of a token. Owners allowed by the collection policy pay a deposit.
+		#[pallet::weight(10_000)]
+		pub fn set_token_metadata(origin: OriginFor<T>, collection_id: u32, token_id: u32, data: BoundedVec<u8, T::StringLimit>) -> DispatchResult {
+			let depositor = Self::ensure_can_edit(origin, collection_id, token_id)?;
+			let amount = T::MetadataDepositBase::get()
+				.saturating_add(T::DepositPerByte::get().saturating_mul((data.len() as u32).into()));
+
+			if let Some(old) = Self::get_token_metadata(collection_id, token_id) {
+				ensure!(depositor.is_none() || old.deposit.is_some(),Error::<T>::LockedByAdmin);
+				Self::refund(old.deposit);
+			}
+			let deposit = Self::take_deposit(depositor, amount)?;
+
+			TokensMetadata::<T>::insert(collection_id, token_id, TokenMetadata::<T> { data: data.clone(), deposit });
+			Self::deposit_event(Event::TokenMetadataSet(collection_id,token_id,data.into_inner()));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn clear_token_metadata(origin: OriginFor<T>, collection_id: u32, token_id: u32) -> DispatchResult {
+			let depositor = Self::ensure_can_edit(origin, collection_id, token_id)?;
+			let old = TokensMetadata::<T>::take(collection_id, token_id).ok_or(Error::<T>::MetadataNotFound)?;
+			ensure!(depositor.is_none() || old.deposit.is_some(),Error::<T>::LockedByAdmin);
+			Self::refund(old.deposit);
+
+			Self::deposit_event(Event::TokenMetadataCleared(collection_id,token_id));
+
+			Ok(())
+		}
+
+		/// Sets an attribute of a token, such as a level or a content hash. Owners allowed by the
+		/// collection policy pay a deposit.
+		#[pallet::weight(10_000)]
+		pub fn set_attribute(origin: OriginFor<T>, collection_id: u32, token_id: u32, key: BoundedVec<u8, T::KeyLimit>, value: BoundedVec<u8, T::ValueLimit>) -> DispatchResult {
+			let depositor = Self::ensure_can_edit(origin, collection_id, token_id)?;
+			let amount = T::AttributeDepositBase::get()
+				.saturating_add(T::DepositPerByte::get().saturating_mul(((key.len() + value.len()) as u32).into()));
+
+			if let Some(old) = Self::get_attribute((collection_id, token_id), &key) {
+				ensure!(depositor.is_none() || old.deposit.is_some(),Error::<T>::LockedByAdmin);
+				Self::refund(old.deposit);
+			}
+			let deposit = Self::take_deposit(depositor, amount)?;
+
+			Attributes::<T>::insert((collection_id, token_id), &key, Attribute::<T> { value: value.clone(), deposit });
+			Self::deposit_event(Event::AttributeSet(collection_id,token_id,key.into_inner(),value.into_inner()));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn clear_attribute(origin: OriginFor<T>, collection_id: u32, token_id: u32, key: BoundedVec<u8, T::KeyLimit>) -> DispatchResult {
+			let depositor = Self::ensure_can_edit(origin, collection_id, token_id)?;
+			let old = Attributes::<T>::take((collection_id, token_id), &key).ok_or(Error::<T>::AttributeNotFound)?;
+			ensure!(depositor.is_none() || old.deposit.is_some(),Error::<T>::LockedByAdmin);
+			Self::refund(old.deposit);
+
+			Self::deposit_event(Event::AttributeCleared(collection_id,token_id,key.into_inner()));
+
+			Ok(())
+		}
+
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Holders of a collection with the id of their token.
+		pub fn holders(collection_id: u32) -> Vec<(u32, T::AccountId)> {
+			Owners::<T>::iter_prefix(collection_id).collect()
+		}
+
+		/// Tokens held by `who`, as collection and token id.
+		pub fn tokens_of(who: &T::AccountId) -> Vec<(u32, u32)> {
+			AccountTokens::<T>::iter_prefix(who).collect()
+		}
+
+		/// Burns the token `who` holds in `collection_id`. Used by other pallets to revoke tokens.
+		pub fn do_burn(who: T::AccountId, collection_id: u32) -> DispatchResult {
 			ensure!(Tokens::<T>::contains_key((who.clone(),collection_id.clone())),Error::<T>::TokenDoesNotExist);
 			let token = Self::get_token((who.clone(),collection_id.clone())).ok_or(Error::<T>::NullValue)?;
 			
@@ -176,6 +483,10 @@ pub mod pallet {
 			let mut active = Self::get_active_tokens(collection_id);
 
 			Tokens::<T>::remove((who.clone(),collection_id.clone()));
+			Approvals::<T>::remove((who.clone(),collection_id.clone()));
+			Owners::<T>::remove(collection_id,uid);
+			AccountTokens::<T>::remove(&who,collection_id);
+			Self::clear_token_data(collection_id, uid);
 			active = active - 1;
 			ActiveTokens::<T>::insert(collection_id.clone(),active);
 
@@ -184,6 +495,90 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Moves the token `from` holds in a transferable collection to `to`, who must not hold
+		/// one already.
+		fn do_transfer(from: T::AccountId, collection_id: u32, to: T::AccountId) -> DispatchResult {
+			let collection = Self::get_collection(collection_id).ok_or(Error::<T>::CollectionDoesNotExist)?;
+			ensure!(collection.transferable,Error::<T>::CollectionNotTransferable);
+			ensure!(from != to,Error::<T>::TransferToSelf);
+			let mut token = Self::get_token((from.clone(),collection_id)).ok_or(Error::<T>::TokenDoesNotExist)?;
+			// Ensure one Token per user policy
+			ensure!(!Tokens::<T>::contains_key((to.clone(),collection_id)),Error::<T>::OneAccountOneToken);
+
+			Tokens::<T>::remove((from.clone(),collection_id));
+			Approvals::<T>::remove((from.clone(),collection_id));
+			token.owner = to.clone();
+			Tokens::<T>::insert((to.clone(),collection_id),&token);
+			Owners::<T>::insert(collection_id,token.id,&to);
+			AccountTokens::<T>::remove(&from,collection_id);
+			AccountTokens::<T>::insert(&to,collection_id,token.id);
+			Self::release_owner_data(collection_id, token.id);
+
+			Self::deposit_event(Event::NFTTransferred(collection_id,token.id,from,to));
+
+			Ok(())
+		}
+
+		/// Checks that `origin` may edit the data of a token, returning the account to take a
+		/// deposit from. Root edits for free, and owners cannot edit what root set.
+		fn ensure_can_edit(origin: OriginFor<T>, collection_id: u32, token_id: u32) -> Result<Option<T::AccountId>, DispatchError> {
+			ensure!(Collections::<T>::contains_key(collection_id),Error::<T>::CollectionDoesNotExist);
+			match ensure_signed_or_root(origin)? {
+				None => {
+					ensure!(Owners::<T>::contains_key(collection_id,token_id),Error::<T>::TokenDoesNotExist);
+					Ok(None)
+				},
+				Some(who) => {
+					ensure!(Self::get_metadata_policy(collection_id) == MetadataPolicy::Owner,Error::<T>::NoPermission);
+					ensure!(Self::get_owner(collection_id,token_id) == Some(who.clone()),Error::<T>::NotTheOwner);
+					Ok(Some(who))
+				},
+			}
+		}
+
+		fn take_deposit(depositor: Option<T::AccountId>, amount: BalanceOf<T>) -> Result<Option<Deposit<T>>, DispatchError> {
+			match depositor {
+				Some(depositor) => {
+					T::Currency::reserve(&depositor, amount)?;
+					Ok(Some(Deposit { depositor, amount }))
+				},
+				None => Ok(None),
+			}
+		}
+
+		fn refund(deposit: Option<Deposit<T>>) {
+			if let Some(deposit) = deposit {
+				T::Currency::unreserve(&deposit.depositor, deposit.amount);
+			}
+		}
+
+		/// Removes the metadata and attributes a previous owner of a transferred token paid for,
+		/// refunding their deposits. Data set by root stays with the token.
+		fn release_owner_data(collection_id: u32, token_id: u32) {
+			if let Some(metadata) = Self::get_token_metadata(collection_id, token_id) {
+				if metadata.deposit.is_some() {
+					TokensMetadata::<T>::remove(collection_id, token_id);
+					Self::refund(metadata.deposit);
+				}
+			}
+			let paid: Vec<_> = Attributes::<T>::iter_prefix((collection_id, token_id))
+				.filter(|(_, attribute)| attribute.deposit.is_some())
+				.collect();
+			for (key, attribute) in paid {
+				Attributes::<T>::remove((collection_id, token_id), &key);
+				Self::refund(attribute.deposit);
+			}
+		}
+
+		/// Removes the metadata and attributes of a burned token, refunding their deposits.
+		fn clear_token_data(collection_id: u32, token_id: u32) {
+			if let Some(metadata) = TokensMetadata::<T>::take(collection_id, token_id) {
+				Self::refund(metadata.deposit);
+			}
+			for (_, attribute) in Attributes::<T>::drain_prefix((collection_id, token_id)) {
+				Self::refund(attribute.deposit);
+			}
+		}
 	}
 
-}
\ No newline at end of file
+}
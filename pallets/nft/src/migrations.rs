@@ -0,0 +1,73 @@
+//! Storage migrations for pallet_nft.
+
+pub mod v1 {
+	use crate::{Collection, Collections, Config, Pallet};
+	use codec::Decode;
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use sp_std::{marker::PhantomData, vec::Vec};
+
+	/// Layout of [`Collection`] before the transferable flag.
+	#[derive(Decode)]
+	struct OldCollection<BlockNumber> {
+		total_supply: u32,
+		created_at: BlockNumber,
+		metadata: Vec<u8>,
+	}
+
+	/// Marks existing collections as soulbound, which is how they behaved until now.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			Collections::<T>::translate::<OldCollection<T::BlockNumber>, _>(|_, old| {
+				translated += 1;
+				Some(Collection {
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					metadata: old.metadata,
+					transferable: false,
+				})
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+	}
+}
+
+pub mod v2 {
+	use crate::{AccountTokens, Config, Owners, Pallet, Tokens};
+	use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+	use sp_std::marker::PhantomData;
+
+	/// Builds the owner and account indexes of existing tokens.
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			for ((owner, collection_id), token) in Tokens::<T>::iter() {
+				reads += 1;
+				writes += 2;
+				Owners::<T>::insert(collection_id, token.id, &owner);
+				AccountTokens::<T>::insert(&owner, collection_id, token.id);
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+	}
+}
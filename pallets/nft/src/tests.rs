@@ -1,12 +1,12 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Error, MetadataPolicy};
+use frame_support::{assert_noop, assert_ok, traits::ReservableCurrency, BoundedVec};
 
 #[test]
 fn it_creates_a_collection_should_work() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
 		// Read pallet storage and assert an expected result.
 		assert_eq!(Nft::get_total_collections(), 1);
 	});
@@ -17,7 +17,7 @@ fn it_mints_a_token_should_work() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
         //Check # of tokens for collection 1
         assert_eq!(Nft::get_active_tokens(1u32),0u32);
 		//Mint a token
@@ -33,7 +33,7 @@ fn it_mints_a_token_should_fail_1() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
         //Check # of tokens for collection 1
         assert_eq!(Nft::get_active_tokens(1u32),0u32);
 		//Mint a token
@@ -51,7 +51,7 @@ fn it_mints_a_token_should_fail_2() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,1,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,1,b"Qualifier".to_vec(),false));
         //Check # of tokens for collection 1
         assert_eq!(Nft::get_active_tokens(1u32),0u32);
 		//Mint a token
@@ -69,7 +69,7 @@ fn it_burns_a_token_should_work() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
         
         assert_eq!(Nft::get_active_tokens(1u32),0u32);
 		//Mint a token
@@ -88,7 +88,7 @@ fn it_burns_a_token_should_fail() {
 	new_test_ext().execute_with(|| {
         assert_eq!(Nft::get_total_collections(), 0);
 		//Dispatch a signed extrinsic.
-		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec()));
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
         
         assert_eq!(Nft::get_active_tokens(1u32),0u32);
 		//Mint a token
@@ -99,4 +99,146 @@ fn it_burns_a_token_should_fail() {
 		assert_noop!(Nft::burn(Origin::signed(1),2),Error::<Test>::TokenDoesNotExist);
 		
 	});
-}
\ No newline at end of file
+}
+#[test]
+fn it_keeps_tokens_soulbound_unless_transferable() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
+		assert_ok!(Nft::mint(Origin::root(),1,1));
+		assert_noop!(Nft::transfer(Origin::signed(1),1,2), Error::<Test>::CollectionNotTransferable);
+		assert_noop!(Nft::approve(Origin::signed(1),1,Some(2)), Error::<Test>::CollectionNotTransferable);
+	});
+}
+
+#[test]
+fn it_transfers_a_token_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Ticket".to_vec(),true));
+		assert_ok!(Nft::mint(Origin::root(),1,1));
+		assert_ok!(Nft::mint(Origin::root(),1,3));
+
+		assert_ok!(Nft::transfer(Origin::signed(1),1,2));
+		assert!(Nft::get_token((1,1)).is_none());
+		assert_eq!(Nft::get_token((2,1)).unwrap().owner, 2);
+		// One token per account still holds
+		assert_noop!(Nft::transfer(Origin::signed(2),1,3), Error::<Test>::OneAccountOneToken);
+
+		assert_noop!(Nft::transfer_from(Origin::signed(4),1,2,1), Error::<Test>::NotApproved);
+		assert_ok!(Nft::approve(Origin::signed(2),1,Some(4)));
+		assert_ok!(Nft::transfer_from(Origin::signed(4),1,2,1));
+		assert_eq!(Nft::get_token((1,1)).unwrap().id, 1);
+		// The approval does not follow the token
+		assert_eq!(Nft::get_approval((2,1)), None);
+		assert_noop!(Nft::transfer_from(Origin::signed(4),1,1,2), Error::<Test>::NotApproved);
+		assert_eq!(Nft::get_active_tokens(1u32),2u32);
+	});
+}
+
+#[test]
+fn it_sets_token_metadata_and_attributes_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Ticket".to_vec(),true));
+		assert_ok!(Nft::mint(Origin::root(),1,1));
+		let cid: BoundedVec<u8, _> = b"QmToken".to_vec().try_into().unwrap();
+		let key: BoundedVec<u8, _> = b"level".to_vec().try_into().unwrap();
+		let value: BoundedVec<u8, _> = b"2".to_vec().try_into().unwrap();
+
+		// Only the admin may edit until the policy allows owners
+		assert_noop!(Nft::set_token_metadata(Origin::signed(1),1,1,cid.clone()), Error::<Test>::NoPermission);
+		assert_ok!(Nft::set_attribute(Origin::root(),1,1,key.clone(),value.clone()));
+		assert_eq!(Nft::get_attribute((1,1),&key).unwrap().deposit, None);
+
+		assert_ok!(Nft::set_metadata_policy(Origin::root(),1,MetadataPolicy::Owner));
+		assert_noop!(Nft::set_token_metadata(Origin::signed(2),1,1,cid.clone()), Error::<Test>::NotTheOwner);
+		assert_ok!(Nft::set_token_metadata(Origin::signed(1),1,1,cid.clone()));
+		// Base deposit plus one per byte
+		assert_eq!(Balances::reserved_balance(1), 17);
+		assert_eq!(Nft::get_token_metadata(1,1).unwrap().data, cid);
+
+		// Data the previous owner paid for is dropped and refunded, data set by root stays
+		assert_ok!(Nft::transfer(Origin::signed(1),1,2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(Nft::get_token_metadata(1,1).is_none());
+		assert_eq!(Nft::get_attribute((1,1),&key).unwrap().value, value);
+
+		// Owners cannot edit what root set
+		assert_noop!(Nft::set_attribute(Origin::signed(2),1,1,key.clone(),value.clone()), Error::<Test>::LockedByAdmin);
+		assert_noop!(Nft::clear_attribute(Origin::signed(2),1,1,key.clone()), Error::<Test>::LockedByAdmin);
+		let color: BoundedVec<u8, _> = b"color".to_vec().try_into().unwrap();
+		assert_ok!(Nft::set_attribute(Origin::signed(2),1,1,color.clone(),value.clone()));
+		assert_eq!(Balances::reserved_balance(2), 11);
+
+		assert_ok!(Nft::burn(Origin::signed(2),1));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(Nft::get_token_metadata(1,1).is_none());
+		assert!(Nft::get_attribute((1,1),&key).is_none());
+		assert!(Nft::get_attribute((1,1),&color).is_none());
+	});
+}
+
+#[test]
+fn it_indexes_token_owners_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Ticket".to_vec(),true));
+		assert_ok!(Nft::create_collection(Origin::root(),2,200,b"Qualifier".to_vec(),false));
+		assert_ok!(Nft::mint(Origin::root(),1,1));
+		assert_ok!(Nft::mint(Origin::root(),1,2));
+		assert_ok!(Nft::mint(Origin::root(),2,1));
+		assert_eq!(Nft::get_owner(1,2), Some(2));
+		let mut tokens = Nft::tokens_of(&1);
+		tokens.sort();
+		assert_eq!(tokens, vec![(1,1),(2,1)]);
+
+		assert_ok!(Nft::transfer(Origin::signed(1),1,3));
+		assert_eq!(Nft::get_owner(1,1), Some(3));
+		assert_eq!(Nft::get_account_token(1,1), None);
+		assert_eq!(Nft::get_account_token(3,1), Some(1));
+
+		assert_ok!(Nft::burn(Origin::signed(2),1));
+		assert_eq!(Nft::get_owner(1,2), None);
+		assert_eq!(Nft::holders(1), vec![(1,3)]);
+		assert_eq!(Nft::tokens_of(&2), vec![]);
+	});
+}
+
+#[test]
+fn it_migrates_collections_to_soulbound() {
+	use frame_support::{storage::unhashed, traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion}};
+
+	new_test_ext().execute_with(|| {
+		// A collection as stored before the transferable flag
+		unhashed::put(&crate::Collections::<Test>::hashed_key_for(1), &(200u32, 5u64, b"Qualifier".to_vec()));
+		StorageVersion::new(0).put::<Nft>();
+
+		crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		let collection = Nft::get_collection(1).unwrap();
+		assert_eq!((collection.total_supply, collection.created_at, collection.metadata), (200, 5, b"Qualifier".to_vec()));
+		assert!(!collection.transferable);
+		assert_eq!(Nft::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn it_migrates_token_indexes() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::root(),1,200,b"Qualifier".to_vec(),false));
+		assert_ok!(Nft::mint(Origin::root(),1,1));
+		assert_ok!(Nft::mint(Origin::root(),1,2));
+		// The tokens as they were indexed before
+		let _ = crate::Owners::<Test>::clear(u32::MAX, None);
+		let _ = crate::AccountTokens::<Test>::clear(u32::MAX, None);
+		StorageVersion::new(1).put::<Nft>();
+
+		crate::migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Nft::get_owner(1,1), Some(1));
+		assert_eq!(Nft::get_owner(1,2), Some(2));
+		assert_eq!(Nft::get_account_token(2,1), Some(2));
+		assert_eq!(Nft::tokens_of(&1), vec![(1,1)]);
+		assert_eq!(Nft::on_chain_storage_version(), 2);
+	});
+}
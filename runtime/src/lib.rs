@@ -30,7 +30,7 @@ use frame_support::{
 	dispatch::DispatchClass,
 	parameter_types,
 	traits::{
-		AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, Everything, KeyOwnerProofSystem,
+		AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, Contains, KeyOwnerProofSystem,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -117,7 +117,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -173,8 +173,21 @@ parameter_types! {
 
 const_assert!(NORMAL_DISPATCH_RATIO.deconstruct() >= AVERAGE_ON_INITIALIZE_RATIO.deconstruct());
 
+/// Rejects bhdao and nft calls while the DAO is paused. Governance handles the incident through
+/// root calls, which bypass the filter.
+pub struct BaseFilter;
+impl Contains<RuntimeCall> for BaseFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		match call {
+			_ if !Bhdao::is_paused() => true,
+			RuntimeCall::Bhdao(_) | RuntimeCall::Nft(_) => false,
+			_ => true,
+		}
+	}
+}
+
 impl frame_system::Config for Runtime {
-	type BaseCallFilter = Everything;
+	type BaseCallFilter = BaseFilter;
 	type BlockWeights = RuntimeBlockWeights;
 	type BlockLength = RuntimeBlockLength;
 	type DbWeight = RocksDbWeight;
@@ -337,15 +350,60 @@ parameter_types! {
 	pub const MaxPeerDataEncodingSize: u32 = 1_000;
 }
 
+parameter_types! {
+	pub const ApplicationBond: Balance = 10 * DOLLARS;
+	pub const ProfileDeposit: Balance = 1 * DOLLARS;
+	pub const MaxQualifiers: u32 = 200;
+	pub const MaxCollectors: u32 = 100;
+	pub const MaxContributors: u32 = 1000;
+	pub const MaxReviewNotesPerVote: u32 = 100;
+	pub const MaxDocumentSize: u64 = 100 * 1024 * 1024;
+	pub const VoteRetentionPeriod: BlockNumber = 30 * DAYS;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_bhdao::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type ApplicationBond = ApplicationBond;
+	type ProfileDeposit = ProfileDeposit;
+	type MaxQualifiers = MaxQualifiers;
+	type MaxCollectors = MaxCollectors;
+	type MaxContributors = MaxContributors;
+	type MaxReviewNotesPerVote = MaxReviewNotesPerVote;
+	type AppCrypto = BhdaoAppCrypto;
+	type VoteRetentionPeriod = VoteRetentionPeriod;
+	type Time = Timestamp;
+	type MaxDocumentSize = MaxDocumentSize;
+	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+}
+
+pub struct BhdaoAppCrypto;
+
+impl frame_system::offchain::AppCrypto<<Signature as traits::Verify>::Signer, Signature>
+	for BhdaoAppCrypto
+{
+	type RuntimeAppPublic = pallet_bhdao::sr25519::AuthorityId;
+	type GenericSignature = sp_core::sr25519::Signature;
+	type GenericPublic = sp_core::sr25519::Public;
+}
+
+parameter_types! {
+	pub const NftMetadataDepositBase: Balance = 1 * DOLLARS;
+	pub const NftAttributeDepositBase: Balance = 50 * CENTS;
+	pub const NftDepositPerByte: Balance = 1 * CENTS;
 }
 
 /// Configure the pallet-nft.
 impl pallet_nft::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type MetadataDepositBase = NftMetadataDepositBase;
+	type AttributeDepositBase = NftAttributeDepositBase;
+	type DepositPerByte = NftDepositPerByte;
+	type StringLimit = ConstU32<256>;
+	type KeyLimit = ConstU32<64>;
+	type ValueLimit = ConstU32<256>;
 }
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
@@ -718,7 +776,13 @@ pub type Executive = frame_executive::Executive<
 
 // All migrations executed on runtime upgrade as a nested tuple of types implementing
 // `OnRuntimeUpgrade`.
-type Migrations = ();
+type Migrations = (
+	pallet_bhdao::migrations::v1::MigrateToV1<Runtime>,
+	pallet_bhdao::migrations::v2::MigrateToV2<Runtime>,
+	pallet_bhdao::migrations::v3::MigrateToV3<Runtime>,
+	pallet_nft::migrations::v1::MigrateToV1<Runtime>,
+	pallet_nft::migrations::v2::MigrateToV2<Runtime>,
+);
 
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]